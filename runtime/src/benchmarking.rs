@@ -0,0 +1,58 @@
+//! ibchain の重み付けのためのベンチマーク
+//!
+//! 測定の仕組みはまだないため、各ベンチマークを準備 (`setup_*`) と測定対象の 1 回の呼び出し (`bench_*`) に分けて定義する
+//! 呼び出しは `Call` を組み立てて dispatch するため、実際のトランザクションと同じ経路を通る
+//! `cargo test --features runtime-benchmarks` で、すべての組み合わせの準備と呼び出しが通ることを確認する
+
+use rstd::prelude::*;
+use support::dispatch::{Dispatchable, Result};
+use system::RawOrigin;
+use crate::ibchain::{Call, Module, Trait};
+
+/// issue で測る資産名の長さ (バイト)
+pub const ISSUE_NAME_LENS: [usize; 3] = [1, 32, 256];
+
+/// ベンチマークで発行する量
+const BENCH_ISSUE_QTY: u64 = 1_000_000;
+/// sendasset で送る量
+const BENCH_SEND_QTY: u64 = 100;
+
+/// sendasset の受信者の状態
+/// 初めて所有する受信者は MyAssets* と保有者配列への登録が加わる
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Recipient {
+    /// 初めて所有する
+    New,
+    /// 既に所有している
+    Existing,
+}
+
+/// sendasset で測る分岐
+pub const SENDASSET_RECIPIENTS: [Recipient; 2] = [Recipient::New, Recipient::Existing];
+
+/// issue の準備
+/// 長さ `name_len` の、どの資産名の規則でも許される資産名を返す
+pub fn setup_issue(name_len: usize) -> Vec<u8> {
+    vec![b'a'; name_len]
+}
+
+/// issue の測定対象
+pub fn bench_issue<T: Trait>(caller: T::AccountId, name: Vec<u8>) -> Result {
+    Call::<T>::issue(name, BENCH_ISSUE_QTY, true).dispatch(RawOrigin::Signed(caller).into())
+}
+
+/// sendasset の準備
+/// `caller` が資産を発行し、`recipient` が Existing なら `to` に一度送っておく。資産 ID を返す
+pub fn setup_sendasset<T: Trait>(caller: T::AccountId, to: T::AccountId, recipient: Recipient) -> rstd::result::Result<T::Hash, &'static str> {
+    bench_issue::<T>(caller.clone(), setup_issue(ISSUE_NAME_LENS[1]))?;
+    let asset_id = Module::<T>::asset_of_owner_by_index((caller.clone(), Module::<T>::owned_asset_count(&caller) - 1));
+    if recipient == Recipient::Existing {
+        bench_sendasset::<T>(caller, to, asset_id)?;
+    }
+    Ok(asset_id)
+}
+
+/// sendasset の測定対象
+pub fn bench_sendasset<T: Trait>(caller: T::AccountId, to: T::AccountId, asset_id: T::Hash) -> Result {
+    Call::<T>::sendasset(to, asset_id, BENCH_SEND_QTY).dispatch(RawOrigin::Signed(caller).into())
+}
@@ -0,0 +1,75 @@
+//! 資産量の表示用変換
+//!
+//! ストレージは整数 (raw) のまま扱い、小数表記との変換はこのモジュールだけで行う
+//! `decimals` は小数点以下の桁数
+
+/// 10^decimals
+/// u64 に収まらない場合は None
+fn scale(decimals: u8) -> Option<u64> {
+    10u64.checked_pow(u32::from(decimals))
+}
+
+/// 整数部と小数部から raw 値を求める
+/// whole: 整数部
+/// frac: 小数部 (10^decimals 未満)
+/// 小数部が大きすぎる場合と、結果が u64 に収まらない場合は None
+pub fn to_raw(whole: u64, frac: u64, decimals: u8) -> Option<u64> {
+    let scale = scale(decimals)?;
+    if frac >= scale {
+        return None;
+    }
+    whole.checked_mul(scale)?.checked_add(frac)
+}
+
+/// raw 値を (整数部, 小数部) に分ける
+pub fn from_raw(raw: u64, decimals: u8) -> (u64, u64) {
+    match scale(decimals) {
+        Some(scale) => (raw / scale, raw % scale),
+        // 10^decimals は u64 の最大値より大きいため、整数部は常に 0
+        None => (0, raw),
+    }
+}
+
+/// テスト
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_raw_scales_whole_and_adds_fraction() {
+        // 1.5 (小数 1 桁)
+        assert_eq!(to_raw(1, 5, 1), Some(15));
+        // 1.05 (小数 2 桁)
+        assert_eq!(to_raw(1, 5, 2), Some(105));
+        assert_eq!(to_raw(7, 0, 0), Some(7));
+        assert_eq!(to_raw(0, 1, 19), Some(1));
+    }
+
+    #[test]
+    fn from_raw_splits_whole_and_fraction() {
+        assert_eq!(from_raw(15, 1), (1, 5));
+        assert_eq!(from_raw(105, 2), (1, 5));
+        assert_eq!(from_raw(7, 0), (7, 0));
+        assert_eq!(from_raw(u64::max_value(), 20), (0, u64::max_value()));
+    }
+
+    #[test]
+    fn round_trip() {
+        for &(whole, frac, decimals) in [(0u64, 0u64, 0u8), (123, 456, 3), (18, 446_744_073_709_551_615, 18)].iter() {
+            let raw = to_raw(whole, frac, decimals).unwrap();
+            assert_eq!(from_raw(raw, decimals), (whole, frac));
+        }
+    }
+
+    #[test]
+    fn to_raw_rejects_overflow_and_oversized_fraction() {
+        // 整数部 * 10^decimals があふれる
+        assert_eq!(to_raw(u64::max_value(), 0, 1), None);
+        // 加算であふれる
+        assert_eq!(to_raw(1_844_674_407_370_955_161, 6, 1), None);
+        // 10^decimals が u64 に収まらない
+        assert_eq!(to_raw(0, 0, 20), None);
+        // 小数部が桁数を超える
+        assert_eq!(to_raw(1, 10, 1), None);
+    }
+}
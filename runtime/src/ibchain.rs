@@ -1,13 +1,16 @@
-use parity_codec::Encode;
+use parity_codec::{Encode, Decode};
 use support::{decl_storage, decl_module, StorageValue, StorageMap,
-    dispatch::Result, ensure, decl_event};
-use system::ensure_signed;
-use runtime_primitives::traits::{Hash};
+    dispatch::Result, ensure, decl_event, Parameter};
+use system::{ensure_signed, ensure_root};
+use runtime_primitives::traits::{As, Hash, Zero, CheckedAdd, Verify};
 use parity_codec_derive::{Encode, Decode};
 
 use rstd::prelude::*;
+#[cfg(feature = "std")]
+use serde_derive::{Serialize, Deserialize};
 
-#[derive(Encode, Decode, Default, Clone, PartialEq)]
+#[derive(Encode, Decode, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize, Debug))]
 
 /// オリジナル資産
 pub struct Asset<Hash> {
@@ -17,26 +20,425 @@ pub struct Asset<Hash> {
     name: Vec<u8>,
     /// 追加発行
     /// true: 追加発行可能
-    open: bool
+    open: bool,
+}
+
+/// RPC 応答用の資産情報
+/// 資産名は UTF-8 として読めれば文字列に、読めなければ "0x" 付きの 16 進に変換する
+/// 元のバイト列は name_bytes に残す
+#[cfg(feature = "std")]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct AssetInfo<Hash> {
+    /// 資産ID
+    pub id: Hash,
+    /// 資産名 (UTF-8 文字列、または 16 進)
+    pub name: String,
+    /// name が UTF-8 として読めた文字列であれば true
+    pub name_is_utf8: bool,
+    /// 資産名のバイト列
+    pub name_bytes: Vec<u8>,
+    /// 追加発行可能
+    pub open: bool,
+}
+
+#[cfg(feature = "std")]
+impl<Hash> From<Asset<Hash>> for AssetInfo<Hash> {
+    fn from(asset: Asset<Hash>) -> Self {
+        let (name, name_is_utf8) = match String::from_utf8(asset.name.clone()) {
+            Ok(name) => (name, true),
+            Err(_) => {
+                let hex: String = asset.name.iter().map(|b| format!("{:02x}", b)).collect();
+                (format!("0x{}", hex), false)
+            }
+        };
+        AssetInfo {
+            id: asset.id,
+            name,
+            name_is_utf8,
+            name_bytes: asset.name,
+            open: asset.open,
+        }
+    }
+}
+
+/// base64url (パディングなし) の文字
+#[cfg(feature = "std")]
+const COMPACT_ID_ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// 資産 ID の短い文字列表現
+/// base64url (パディングなし) で、32 バイトの ID は 43 文字になる ("0x" 付きの 16 進は 66 文字)
+#[cfg(feature = "std")]
+pub fn encode_compact_id(id: &[u8]) -> String {
+    let mut out = String::with_capacity((id.len() * 4 + 2) / 3);
+    for chunk in id.chunks(3) {
+        let bits = chunk.iter().enumerate().fold(0u32, |bits, (i, b)| bits | (u32::from(*b) << (16 - 8 * i)));
+        for i in 0..=chunk.len() {
+            out.push(COMPACT_ID_ALPHABET[((bits >> (18 - 6 * i)) & 0x3f) as usize] as char);
+        }
+    }
+    out
+}
+
+/// encode_compact_id の逆変換
+/// base64url (パディングなし) として読めなければ None
+#[cfg(feature = "std")]
+pub fn decode_compact_id(s: &str) -> Option<Vec<u8>> {
+    if s.len() % 4 == 1 {
+        return None;
+    }
+    let mut out = Vec::with_capacity(s.len() * 3 / 4);
+    for chunk in s.as_bytes().chunks(4) {
+        let mut bits = 0u32;
+        for (i, c) in chunk.iter().enumerate() {
+            let value = COMPACT_ID_ALPHABET.iter().position(|a| a == c)? as u32;
+            bits |= value << (18 - 6 * i);
+        }
+        for i in 0..chunk.len() - 1 {
+            out.push((bits >> (16 - 8 * i)) as u8);
+        }
+    }
+    Some(out)
+}
+
+/// 資産 ID を短い文字列にした RPC 応答用の資産情報
+/// 一覧で多くの ID を返す場合に使う。ID は encode_compact_id の形式
+/// 既定の AssetInfo は互換のため ID を 16 進のまま返す
+#[cfg(feature = "std")]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct CompactAssetInfo {
+    /// 資産ID (base64url)
+    pub id: String,
+    /// 資産名 (UTF-8 文字列、または 16 進)
+    pub name: String,
+    /// name が UTF-8 として読めた文字列であれば true
+    pub name_is_utf8: bool,
+    /// 資産名のバイト列
+    pub name_bytes: Vec<u8>,
+    /// 追加発行可能
+    pub open: bool,
+}
+
+#[cfg(feature = "std")]
+impl<Hash: AsRef<[u8]>> From<AssetInfo<Hash>> for CompactAssetInfo {
+    fn from(info: AssetInfo<Hash>) -> Self {
+        CompactAssetInfo {
+            id: encode_compact_id(info.id.as_ref()),
+            name: info.name,
+            name_is_utf8: info.name_is_utf8,
+            name_bytes: info.name_bytes,
+            open: info.open,
+        }
+    }
+}
+
+/// ブリッジロック情報
+/// 他チェーンのリレイヤはこの情報を元に送信先チェーンで資産を解放する
+#[derive(Encode, Decode, Default, Clone, PartialEq)]
+pub struct BridgeLock<AccountId, Hash> {
+    /// ロックしたアカウント
+    who: AccountId,
+    /// 資産ID
+    asset_id: Hash,
+    /// ロック量 (解放された分を差し引いた残量)
+    qty: u64,
+    /// 送信先チェーンのアドレス
+    target: Vec<u8>,
+}
+
+/// 受信者の承諾待ちの送信
+/// 送信量は承諾または取り消しまでエスクローアカウントが預かる
+#[derive(Encode, Decode, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct PendingTransfer<AccountId, Hash> {
+    /// 送信者
+    pub from: AccountId,
+    /// 受信者
+    pub to: AccountId,
+    /// 資産ID
+    pub asset_id: Hash,
+    /// 送信量
+    pub qty: u64,
+}
+
+/// 委員会が承認して実行する発行者操作
+/// 実行時は発行者の権限で対応する関数を呼ぶ
+#[derive(Encode, Decode, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum AdminAction {
+    /// issuemore (追加発行量)
+    Mint(u64),
+    /// set_max_holders (上限)
+    SetMaxHolders(u64),
+    /// set_max_mint_per_call (上限)
+    SetMaxMintPerCall(u64),
+    /// set_requires_allowlist (有効・無効)
+    SetRequiresAllowlist(bool),
+}
+
+/// 承認待ちの委員会操作
+#[derive(Encode, Decode, Clone, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct AdminProposal<AccountId, Hash> {
+    /// 資産ID
+    pub asset_id: Hash,
+    /// 操作
+    pub action: AdminAction,
+    /// 承認した委員 (提案者を含む)
+    pub approvals: Vec<AccountId>,
+}
+
+/// 資産名に使える文字の規則
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize, Debug))]
+pub enum NamePolicy {
+    /// 表示可能な ASCII 文字 (0x20 - 0x7E) のみ
+    AsciiOnly,
+    /// 正しい UTF-8 であれば可
+    Utf8,
+    /// 制御文字を含まない UTF-8
+    Printable,
+}
+
+impl Default for NamePolicy {
+    fn default() -> Self {
+        NamePolicy::Printable
+    }
+}
+
+impl NamePolicy {
+    /// 資産名がこの規則を満たすか
+    pub fn allows(&self, name: &[u8]) -> bool {
+        match self {
+            NamePolicy::AsciiOnly => name.iter().all(|b| *b >= 0x20 && *b <= 0x7e),
+            NamePolicy::Utf8 => rstd::str::from_utf8(name).is_ok(),
+            NamePolicy::Printable => match rstd::str::from_utf8(name) {
+                Ok(name) => !name.chars().any(|c| c.is_control()),
+                Err(_) => false,
+            },
+        }
+    }
+}
+
+/// アカウントの概要
+/// ウォレットのトップ画面で必要な情報を 1 回で返すためのもの
+#[derive(Encode, Decode, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct AccountOverview<Hash> {
+    /// 所有している資産 (資産ID, 所有量)
+    pub held: Vec<(Hash, u64)>,
+    /// held が MAX_LIST_LEN 件で打ち切られていれば true
+    pub held_truncated: bool,
+    /// 発行した資産
+    pub issued: Vec<Hash>,
+    /// issued が MAX_LIST_LEN 件で打ち切られていれば true
+    pub issued_truncated: bool,
+}
+
+/// 発行時の制約
+/// フロントエンドが署名を求める前に入力を確認するためのもの
+#[derive(Encode, Decode, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct IssuancePolicy {
+    /// 最小発行量
+    pub min_issue_qty: u64,
+    /// 資産名に使える文字の規則
+    pub name_policy: NamePolicy,
+    /// issue_collection で 1 回に発行できる資産数
+    pub max_collection_items: u32,
+}
+
+/// 資産の統計
+#[derive(Encode, Decode, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct AssetStats {
+    /// 発行済量
+    pub total_issued: u64,
+    /// 保有者数
+    pub holder_count: u64,
+    /// 累計移動量
+    pub total_volume: u128,
+}
+
+/// 資産の送信・発行に関する設定
+/// 個別のストレージを 1 回で読めるようにまとめたもの
+#[derive(Encode, Decode, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct AssetConfig<AccountId, BlockNumber> {
+    /// 追加発行できるか
+    pub open: bool,
+    /// 焼却できるか
+    pub burnable: bool,
+    /// 凍結中か
+    pub frozen: bool,
+    /// 送信にメモが必要か
+    pub require_memo: bool,
+    /// 送信者・受信者が許可リストに載っている必要があるか
+    pub requires_allowlist: bool,
+    /// 許可された送信先にしか送れないか
+    pub restrict_destinations: bool,
+    /// 送信イベントを出すか
+    pub events_enabled: bool,
+    /// コミュニティ基金
+    pub community_fund: Option<AccountId>,
+    /// コミュニティ基金への分配率 (万分率)
+    pub community_bps: u16,
+    /// 送信時の焼却率 (万分率)
+    pub burn_bps: u16,
+    /// 保有者数の上限, 0 なら制限なし
+    pub max_holders: u64,
+    /// 1 回の追加発行量の上限, 0 なら制限なし
+    pub max_mint_per_call: u64,
+    /// 送信間隔 (ブロック数), 0 なら制限なし
+    pub cooldown: BlockNumber,
+}
+
+/// 資産ごとに保持する発行者操作の記録の最大数
+const MAX_OWNER_ACTIONS: usize = 20;
+/// 署名付き交換の署名内容の先頭 (他の用途の署名と区別する)
+const SWAP_CONTEXT: &[u8] = b"ibchain/swap";
+/// 一覧 API で 1 回に返す最大件数
+const MAX_LIST_LEN: u64 = 1000;
+/// top_holders で返す最大件数
+const MAX_TOP_HOLDERS: u32 = 100;
+/// 一括送信で扱える送信先の最大数
+const MAX_BATCH_LEN: usize = 100;
+/// 万分率 (bps) の分母
+const BPS_DENOMINATOR: u64 = 10_000;
+/// 資産ごとに保持する発行量履歴の最大数
+const MAX_SUPPLY_CHECKPOINTS: usize = 50;
+/// 1 つの使用者に対して記録する使用許可の最大数
+const MAX_SPENDER_APPROVALS: usize = 100;
+/// 1 つのアカウントが関わる承諾待ち送信の最大数
+const MAX_PENDING_PER_ACCOUNT: usize = 50;
+/// 委員会の最大人数
+const MAX_COMMITTEE_LEN: usize = 20;
+/// 送信メモの最大長 (バイト)
+const MAX_MEMO_LEN: usize = 128;
+/// verify_asset で読む保有者の最大数
+const MAX_VERIFY_HOLDERS: u64 = 1000;
+/// 1 ブロックで期限を迎える承諾待ち送信の最大数
+const MAX_EXPIRING_PER_BLOCK: usize = 100;
+/// 猶予付きの所有者変更の猶予の初期値 (ブロック数、6 秒のブロックで約 1 日)
+const DEFAULT_OWNER_ROTATION_DELAY: u64 = 14_400;
+/// イベントの出力レベル: 出力しない
+pub const EVENTS_OFF: u8 = 0;
+/// イベントの出力レベル: 所有量と所有者の変化を追うのに必要なイベントのみ
+pub const EVENTS_CORE: u8 = 1;
+/// イベントの出力レベル: すべてのイベント
+pub const EVENTS_DETAILED: u8 = 2;
+/// storage_footprint で見込むストレージキー 1 件の大きさ (バイト)
+const STORAGE_KEY_LEN: u64 = 32;
+/// 送信の制限 (transfer_restrictions のビット): 存在しない資産
+pub const RESTRICTION_UNKNOWN_ASSET: u32 = 1 << 0;
+/// 送信の制限: 資産が凍結中
+pub const RESTRICTION_FROZEN: u32 = 1 << 1;
+/// 送信の制限: 受信者がモジュール管理アカウント
+pub const RESTRICTION_RESERVED_RECIPIENT: u32 = 1 << 2;
+/// 送信の制限: 受信者が受け取りを拒否している
+pub const RESTRICTION_OPTED_OUT: u32 = 1 << 3;
+/// 送信の制限: 送信者が許可リストに載っていない
+pub const RESTRICTION_SENDER_NOT_ALLOWLISTED: u32 = 1 << 4;
+/// 送信の制限: 受信者が許可リストに載っていない
+pub const RESTRICTION_RECIPIENT_NOT_ALLOWLISTED: u32 = 1 << 5;
+/// 送信の制限: 許可された送信先でない
+pub const RESTRICTION_DESTINATION_NOT_ALLOWED: u32 = 1 << 6;
+/// 送信の制限: 送信間隔が過ぎていない
+pub const RESTRICTION_COOLDOWN: u32 = 1 << 7;
+/// 送信の制限: メモが必要 (sendasset_with_memo でのみ送れる)
+pub const RESTRICTION_MEMO_REQUIRED: u32 = 1 << 8;
+
+/// 資産ごとの残高を扱う共通インターフェース
+/// 他のモジュールが ibchain の資産を一様に扱えるようにする
+pub trait Fungible<AccountId, AssetId> {
+    /// 発行済量
+    fn total_issuance(asset_id: AssetId) -> u64;
+    /// 所有量
+    fn free_balance(who: &AccountId, asset_id: AssetId) -> u64;
+    /// 資産送信 (sendasset と同じく送信ポリシーを確認し、分配と焼却を差し引く)
+    fn transfer(from: &AccountId, to: &AccountId, asset_id: AssetId, qty: u64) -> Result;
+    /// `qty` を焼却できるか (投票ロック中の量は除く)
+    fn can_slash(who: &AccountId, asset_id: AssetId, qty: u64) -> bool;
 }
 
 pub trait Trait: balances::Trait {
     /// イベント
     type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+    /// 署名 (atomic_swap_signed で相手の同意を確かめる)
+    type Signature: Parameter + Verify<Signer = Self::AccountId>;
 }
 
 decl_event!(
     pub enum Event<T>
     where
         <T as system::Trait>::AccountId,
-        <T as system::Trait>::Hash
+        <T as system::Trait>::Hash,
+        <T as system::Trait>::BlockNumber
     {
-        /// オリジナル資産発行
-        Issued(AccountId, Hash),
-        /// 追加発行
-        IssuedMore(AccountId, Hash, u64),
-        /// 資産送信
-        SentAsset(AccountId, AccountId, Hash, u64),
+        /// オリジナル資産発行 (発行者, 資産ID, イベント番号, 発行者の操作番号)
+        Issued(AccountId, Hash, u64, u64),
+        /// 追加発行 (発行者, 資産ID, 量, イベント番号, 発行者の操作番号)
+        /// フォーセットでは発行者の代わりに受け取ったアカウント
+        IssuedMore(AccountId, Hash, u64, u64, u64),
+        /// 資産送信 (送信者, 受信者, 資産ID, 量, イベント番号, 送信者の操作番号)
+        SentAsset(AccountId, AccountId, Hash, u64, u64, u64),
+        /// メモ付き資産送信 (送信者, 受信者, 資産ID, 量, メモ)
+        SentWithMemo(AccountId, AccountId, Hash, u64, Vec<u8>),
+        /// 受取先への送信 (送信者, 本来の受信者, 受取先, 資産ID, 量)
+        DeliveredToFallback(AccountId, AccountId, AccountId, Hash, u64),
+        /// 一括送信のまとめ (資産ID, 送信者, 送信先数, 送信総量)
+        BatchSent(Hash, AccountId, u64, u64),
+        /// ブリッジへのロック (アカウント, ロックID, 資産ID, 量)
+        BridgeLocked(AccountId, Hash, Hash, u64),
+        /// ブリッジからの解放 (受信者, 資産ID, 量)
+        BridgeReleased(AccountId, Hash, u64),
+        /// 使用許可量の設定 (所有者, 使用者, 資産ID, 許可量)
+        Approved(AccountId, AccountId, Hash, u64),
+        /// 資産焼却 (所有者, 資産ID, 量, イベント番号, 所有者の操作番号)
+        Burned(AccountId, Hash, u64, u64, u64),
+        /// 投票ロック (アカウント, 資産ID, 量, 解除可能ブロック)
+        VoteLocked(AccountId, Hash, u64, BlockNumber),
+        /// 投票ロック解除 (アカウント, 資産ID)
+        VoteUnlocked(AccountId, Hash),
+        /// ラップ (アカウント, 元資産ID, ラップ資産ID, 量)
+        Wrapped(AccountId, Hash, Hash, u64),
+        /// アンラップ (アカウント, ラップ資産ID, 量)
+        Unwrapped(AccountId, Hash, u64),
+        /// エアドロップ作成 (発行者, エアドロップID, 資産ID, 配布総量)
+        AirdropCreated(AccountId, Hash, Hash, u64),
+        /// エアドロップ受け取り (受取人, エアドロップID, 量)
+        AirdropClaimed(AccountId, Hash, u64),
+        /// フォーセットの受け取り (受取人, 資産ID, 量)
+        FaucetClaimed(AccountId, Hash, u64),
+        /// 所有者変更の提案 (資産ID, 提案された所有者)
+        OwnershipProposed(Hash, AccountId),
+        /// 所有者変更 (資産ID, 旧所有者, 新所有者)
+        OwnershipTransferred(Hash, AccountId, AccountId),
+        /// 猶予付きの所有者変更の開始 (資産ID, 新しい所有者, 確定できる最初のブロック)
+        OwnerRotationBegun(Hash, AccountId, BlockNumber),
+        /// 猶予付きの所有者変更の取り消し (資産ID)
+        OwnerRotationCancelled(Hash),
+        /// 委員会操作の提案 (提案ID, 資産ID, 提案者)
+        AdminActionProposed(Hash, Hash, AccountId),
+        /// 委員会操作の承認 (提案ID, 承認者)
+        AdminActionApproved(Hash, AccountId),
+        /// 委員会操作の実行 (提案ID)
+        AdminActionExecuted(Hash),
+        /// 請求書の支払い (請求書ID, 送信者, 受信者, 資産ID, 量)
+        InvoicePaid(Hash, AccountId, AccountId, Hash, u64),
+        /// 承諾待ち送信の作成 (送信ID, 送信者, 受信者, 資産ID, 量)
+        TransferProposed(Hash, AccountId, AccountId, Hash, u64),
+        /// 承諾待ち送信の承諾 (送信ID)
+        TransferAccepted(Hash),
+        /// 承諾待ち送信の取り消し (送信ID)
+        TransferCancelled(Hash),
+        /// 期限付き送信の期限切れによる返却 (送信ID)
+        TransferExpired(Hash),
+        /// 署名付き交換 (提出者, 相手, 交換番号)
+        SwappedSigned(AccountId, AccountId, u64),
+        /// コミュニティ基金の設定 (資産ID, 基金アカウント, 万分率)
+        CommunityFundSet(Hash, Option<AccountId>, u16),
+        /// コミュニティ基金への分配 (送信者, 基金アカウント, 資産ID, 量)
+        CommunityFunded(AccountId, AccountId, Hash, u64),
     }
 );
 
@@ -54,6 +456,7 @@ decl_storage! {
         /// 全オリジナル資産配列 インデックス => 資産ID
         AllAssetsArray get(asset_by_index): map u64 => T::Hash;
         /// 全オリジナル資産数
+        // u64 のまま。上限に達すると issue は失敗するが、1 ブロック 1 件でも数千億年かかる
         AllAssetsCount get(all_asset_count): u64;
         /// 全オリジナル資産配列におけるインデックス  資産ID => オリジナル資産インデックス
         AllAssetsIndex: map T::Hash => u64;
@@ -61,20 +464,46 @@ decl_storage! {
         /// 発行したオリジナル資産配列
         OwnedAssetsArray get(asset_of_owner_by_index): map (T::AccountId, u64) => T::Hash;
         /// 発行したオリジナル資産数
+        // 上限に達したアカウントは新規発行できない (他のアカウントには影響しない)
         OwnedAssetsCount get(owned_asset_count): map T::AccountId => u64;
         /// 発行したオリジナル資産インデックス
-        // オーナーは 1 人であるため AccountId は不要
+        // 資産ごとのオーナーはある時点で常に 1 人であるため AccountId は不要
+        // 値は現在のオーナー (AssetOwner) の配列での位置を指す。所有者変更では新オーナーでの位置に書き換える
+        // 別々のオーナーの配列で同じ位置にある資産同士でも、キーの資産 ID が異なるため衝突しない
         OwnedAssetsIndex: map T::Hash => u64;
 
+        /// 所有者変更の提案 資産 ID => 提案された所有者
+        /// 提案された所有者が accept_ownership するまで所有者は変わらない
+        PendingOwner get(pending_owner): map T::Hash => Option<T::AccountId>;
+        /// 猶予付きの所有者変更 資産 ID => (新しい所有者, 確定できる最初のブロック)
+        /// 猶予中であれば現在の所有者が取り消せる
+        OwnerRotations get(owner_rotation): map T::Hash => Option<(T::AccountId, T::BlockNumber)>;
+        /// 猶予付きの所有者変更の猶予 (ブロック数, sudo で変更できる)
+        /// 猶予なしでは取り消す間がないため、0 にはしない
+        OwnerRotationDelay get(owner_rotation_delay): T::BlockNumber =
+            <T::BlockNumber as As<u64>>::sa(DEFAULT_OWNER_ROTATION_DELAY);
+
         /// 発行済オリジナル資産量
         /// 資産 ID => 発行量
         /// 資産を追加発行した場合は、この値を更新する
         TotalIssuedAssets get(total_issued_asset): map T::Hash => u64;
+        /// 発行量の履歴 資産 ID => (ブロック番号, 発行量) の配列
+        /// 新しいものほど後ろ。MAX_SUPPLY_CHECKPOINTS を超えたら古いものから捨てる
+        SupplyCheckpoints get(supply_history): map T::Hash => Vec<(T::BlockNumber, u64)>;
 
         Nonce: u64;
 
         // ----------- オリジナル資産管理 --- ここまで
 
+        // ----------- コレクション
+
+        /// 資産が属するコレクション 資産 ID => コレクション名
+        Collection get(collection_of): map T::Hash => Vec<u8>;
+        /// コレクションに属する資産 コレクション名 => 資産 ID の配列 (発行順)
+        CollectionAssets get(collection_assets): map Vec<u8> => Vec<T::Hash>;
+
+        // ----------- コレクション --- ここまで
+
         // ----------- 所有している資産の管理
         
         /// インデックス => 資産 ID
@@ -86,9 +515,214 @@ decl_storage! {
         /// 所有資産量
         /// 資産 ID => 所有量
         MyAssetBalances get(my_asset_balance): map (T::AccountId, T::Hash) => u64;
+        /// 保有者数 資産 ID => 所有量が 0 より大きいアカウント数
+        HolderCount get(holder_count): map T::Hash => u64;
+        /// 保有者配列 (資産 ID, インデックス) => アカウント
+        /// 初めて所有したときに追加し、所有量が 0 になっても残す (MyAssets* と同じ)
+        /// exit_all で MyAssets* から取り除く場合は、こちらからも取り除く
+        AssetHoldersArray get(asset_holder_by_index): map (T::Hash, u64) => T::AccountId;
+        /// 保有者配列の長さ 資産 ID => 登録数
+        AssetHoldersCount get(asset_holders_count): map T::Hash => u64;
+        /// 保有者配列におけるインデックス (資産 ID, アカウント) => インデックス
+        AssetHoldersIndex: map (T::Hash, T::AccountId) => u64;
+        /// 累計移動量 資産 ID => これまでに送信された量の合計
+        /// モジュール管理アカウントとの間の移動も含む。u64 の合計があふれないよう u128 で持つ
+        /// 参考値のため、u128 の最大値に達した後は増えない (送信は失敗しない)
+        TotalVolume get(total_volume): map T::Hash => u128;
+
+        /// 受け取り拒否 (アカウント, 資産 ID) => 拒否していれば true
+        AssetOptOut get(asset_opt_out): map (T::AccountId, T::Hash) => bool;
+
+        /// 投票ロック
+        /// (アカウント, 資産 ID) => (ロック量, 解除可能になるブロック)
+        /// ロック中の量は送信・焼却できない
+        VoteLocks get(vote_lock): map (T::AccountId, T::Hash) => (u64, T::BlockNumber);
+
+        /// 使用許可量
+        /// (所有者, 使用者, 資産 ID) => 許可量
+        Allowances get(allowance): map (T::AccountId, T::AccountId, T::Hash) => u64;
+        /// 使用許可の逆引き 使用者 => (所有者, 資産 ID) の配列
+        /// 許可量が 0 でないものだけを持つ。MAX_SPENDER_APPROVALS 件まで
+        SpenderApprovals get(spender_approvals): map T::AccountId => Vec<(T::AccountId, T::Hash)>;
 
         // ----------- 所有している資産の管理 --- ここまで
 
+        // ----------- 送信ポリシー
+
+        /// コミュニティ基金 資産 ID => 基金アカウント
+        CommunityFund get(community_fund): map T::Hash => Option<T::AccountId>;
+        /// コミュニティ基金への分配率 (万分率) 資産 ID => bps
+        CommunityBps get(community_bps): map T::Hash => u16;
+        /// 送信時の焼却率 (万分率) 資産 ID => bps, 0 なら焼却しない
+        BurnBps get(burn_bps): map T::Hash => u16;
+        /// 焼却できない資産 資産 ID => 焼却できなければ true
+        /// open == false と合わせて発行済量が変わらない
+        NonBurnable get(non_burnable): map T::Hash => bool;
+        /// メモ必須 資産 ID => 必須なら true
+        /// 必須の資産は send_asset_with_memo でしか送信できない
+        RequireMemo get(require_memo): map T::Hash => bool;
+        /// 保有者数の上限 資産 ID => 上限, 0 なら制限なし
+        MaxHolders get(max_holders): map T::Hash => u64;
+        /// 1 回の追加発行量の上限 資産 ID => 上限, 0 なら制限なし
+        MaxMintPerCall get(max_mint_per_call): map T::Hash => u64;
+        /// 発行者の権限操作の記録 資産 ID => (ブロック番号, 操作名) の配列
+        /// 新しいものほど後ろ。MAX_OWNER_ACTIONS を超えたら古いものから捨てる
+        OwnerActionLog get(owner_action_log): map T::Hash => Vec<(T::BlockNumber, Vec<u8>)>;
+        /// 送信間隔 (ブロック数) 資産 ID => 間隔, 0 なら制限なし
+        Cooldown get(cooldown): map T::Hash => T::BlockNumber;
+        /// 最後に送信したブロック (アカウント, 資産 ID) => ブロック番号
+        /// 送信間隔が設定されている資産についてのみ記録する
+        LastSend get(last_send): map (T::AccountId, T::Hash) => Option<T::BlockNumber>;
+        /// 送信イベントの有効・無効 資産 ID => 有効なら true
+        /// 無効にした資産の送信はイベントから追えなくなる
+        /// インデクサは残高をストレージから読む必要があり、EventSeq の抜けも送信とは区別できない
+        EventsEnabled get(events_enabled): map T::Hash => bool = true;
+        /// 許可リスト制 資産 ID => 有効なら true
+        /// 有効な資産は送信者・受信者とも許可リストに載っている必要がある (発行者は常に許可)
+        RequiresAllowlist get(requires_allowlist): map T::Hash => bool;
+        /// 許可リスト (資産 ID, アカウント) => 許可されていれば true
+        Allowlisted get(allowlisted): map (T::Hash, T::AccountId) => bool;
+        /// 送信先制限 資産 ID => 有効なら true
+        /// 有効な資産は許可された送信先 (と発行者) にしか送れない。送信者は制限しない
+        RestrictDestinations get(restrict_destinations): map T::Hash => bool;
+        /// 許可された送信先 (資産 ID, アカウント) => 許可されていれば true
+        AllowedDestinations get(allowed_destination): map (T::Hash, T::AccountId) => bool;
+        /// 受取先 資産 ID => 受信者側の制限で届けられない送信の送り先
+        /// send_asset_or_fallback でのみ使う
+        FallbackAccount get(fallback_account): map T::Hash => Option<T::AccountId>;
+        /// 凍結 資産 ID => 凍結中なら true
+        /// 凍結中の資産は、モジュール管理アカウントとの間も含めて移動できない (発行・焼却はできる)
+        FrozenAssets get(frozen): map T::Hash => bool;
+        /// 厳格モード (sudo で変更できる)
+        /// 有効な場合、sendasset 1 回で新しく保有者になるアカウントを 1 つまでに制限する
+        StrictHolderMode get(strict_holder_mode): bool;
+
+        // ----------- 送信ポリシー --- ここまで
+
+        // ----------- イベント
+
+        /// イベント番号
+        /// Issued / IssuedMore / SentAsset / Burned を発行するたびに 1 増える
+        /// インデクサは番号の抜けでイベントの取りこぼしやリオーグを検出できる
+        EventSeq get(event_seq): u64;
+        /// イベントの出力レベル (EVENTS_OFF / EVENTS_CORE / EVENTS_DETAILED)
+        /// 抑制したイベントのイベント番号・操作番号も進む
+        /// インデクサは設定されたレベルを知っている必要がある (EVENTS_CORE では Approved などが出ない)
+        EventVerbosity get(event_verbosity) config(): u8 = EVENTS_DETAILED;
+        /// アカウントごとの操作番号 アカウント => 次の番号
+        /// Issued / IssuedMore / SentAsset / Burned の対象アカウント (発行者・送信者・焼却される所有者) で 1 増える
+        /// system の nonce と異なり ibchain の操作だけを数えるため、インデクサはアカウントごとの順序を復元できる
+        AccountTxNonce get(account_tx_nonce): map T::AccountId => u64;
+
+        // ----------- イベント --- ここまで
+
+        // ----------- 発行ポリシー
+
+        /// 発行時の最小発行量
+        /// ネットワークごとにジェネシスで設定し、sudo で変更できる
+        MinIssueQty get(min_issue_qty) config(): u64 = 1;
+        /// 資産名に使える文字の規則
+        AssetNamePolicy get(name_policy) config(): NamePolicy;
+        /// 1 ブロックで発行できる資産数 (sudo で変更できる)
+        MaxIssuesPerBlock get(max_issues_per_block): u32 = 100;
+        /// このブロックで発行された資産数 (ブロックの初めに 0 に戻す)
+        IssuesThisBlock get(issues_this_block): u32;
+
+        // ----------- 発行ポリシー --- ここまで
+
+        // ----------- ブリッジ
+
+        /// ブリッジ解放を実行できるアカウント (sudo で設定)
+        BridgeAuthority get(bridge_authority): Option<T::AccountId>;
+        /// ブリッジロック ロックID => ロック情報
+        /// release_from_bridge で残量が 0 になると削除する
+        BridgeLocks get(bridge_lock): map T::Hash => Option<BridgeLock<T::AccountId, T::Hash>>;
+
+        // ----------- ブリッジ --- ここまで
+
+        // ----------- ラップ
+
+        /// ラップ資産の裏付け ラップ資産 ID => (元資産 ID, ラップアカウントにロック中の量)
+        /// ラップ資産の発行済量はロック中の量と常に等しい
+        WrappedBacking get(wrapped_backing): map T::Hash => Option<(T::Hash, u64)>;
+
+        // ----------- ラップ --- ここまで
+
+        // ----------- 承諾待ち送信
+
+        /// 承諾待ち送信 送信 ID => 送信内容
+        PendingTransfers get(pending_transfer): map T::Hash => Option<PendingTransfer<T::AccountId, T::Hash>>;
+        /// アカウントが送信者または受信者である承諾待ち送信 アカウント => 送信 ID の配列
+        /// MAX_PENDING_PER_ACCOUNT 件まで
+        EscrowsByParty get(escrows_of): map T::AccountId => Vec<T::Hash>;
+        /// 期限付き送信の期限 送信 ID => 受信確認できる最後のブロック
+        TransferExpiry get(transfer_expiry): map T::Hash => Option<T::BlockNumber>;
+        /// 期限を迎える送信 ブロック番号 => 送信 ID の配列
+        /// そのブロックの終わりに、受信確認されていない送信を送信者へ戻す。MAX_EXPIRING_PER_BLOCK 件まで
+        ExpiringTransfers get(expiring_transfers): map T::BlockNumber => Vec<T::Hash>;
+        /// 署名付き交換の次の番号 (署名した相手, 提出者) => 番号
+        SwapNonce get(swap_nonce): map (T::AccountId, T::AccountId) => u64;
+
+        // ----------- 承諾待ち送信 --- ここまで
+
+        // ----------- 委員会
+
+        /// 委員会 資産 ID => (委員, 実行に必要な承認数)
+        Committee get(committee): map T::Hash => (Vec<T::AccountId>, u32);
+        /// 承認待ちの委員会操作 提案 ID => 提案内容
+        AdminProposals get(admin_proposal): map T::Hash => Option<AdminProposal<T::AccountId, T::Hash>>;
+
+        // ----------- 委員会 --- ここまで
+
+        // ----------- 請求書
+
+        /// 請求書の支払い記録
+        /// 請求書 ID => (送信者, 受信者, 資産 ID, 量, 支払ったブロック)
+        Invoices get(invoice): map T::Hash => Option<(T::AccountId, T::AccountId, T::Hash, u64, T::BlockNumber)>;
+
+        // ----------- 請求書 --- ここまで
+
+        // ----------- エアドロップ
+
+        /// エアドロップ エアドロップ ID => (マークルルート, 資産 ID)
+        /// 葉は hash((アカウント, 量))、節は小さい方を先にした hash((左, 右))
+        MerkleAirdrops get(merkle_airdrop): map T::Hash => Option<(T::Hash, T::Hash)>;
+        /// エアドロップの残量 エアドロップ ID => エアドロップアカウントに残っている量
+        AirdropRemaining get(airdrop_remaining): map T::Hash => u64;
+        /// 受け取り済み (エアドロップ ID, アカウント) => 受け取り済みなら true
+        AirdropClaims get(airdrop_claimed): map (T::Hash, T::AccountId) => bool;
+
+        // ----------- エアドロップ --- ここまで
+
+        // ----------- フォーセット (テストネット用)
+
+        /// フォーセットの追加発行量 資産 ID => 1 回の量, 0 なら無効
+        FaucetAmount get(faucet_amount): map T::Hash => u64;
+        /// フォーセットの受け取り間隔 (ブロック数), 0 なら制限なし
+        /// 資産ごとではなくアカウントごとに数える
+        FaucetCooldown get(faucet_cooldown): T::BlockNumber;
+        /// 最後にフォーセットを受け取ったブロック アカウント => ブロック番号
+        LastFaucet get(last_faucet): map T::AccountId => Option<T::BlockNumber>;
+
+        // ----------- フォーセット --- ここまで
+
+    }
+    add_extra_genesis {
+        /// ジェネシスで発行する資産 (発行者, 資産名, 発行量)
+        /// 資産 ID は issue_named と同じく資産名のハッシュ。追加発行は不可
+        config(genesis_assets): Vec<(T::AccountId, Vec<u8>, u64)>;
+
+        build(|storage: &mut runtime_primitives::StorageOverlay, _: &mut runtime_primitives::ChildrenStorageOverlay, config: &GenesisConfig<T>| {
+            runtime_io::with_storage(storage, || {
+                for (owner, name, qty) in &config.genesis_assets {
+                    let asset_id = <T as system::Trait>::Hashing::hash_of(name);
+                    Module::<T>::_issue_with_id(owner.clone(), asset_id, name.clone(), *qty, false)
+                        .expect("genesis asset must satisfy the issuance policy");
+                }
+                // ジェネシスの発行はブロックごとの発行数に数えない
+                <IssuesThisBlock<T>>::kill();
+            });
+        });
     }
 }
 
@@ -99,6 +733,14 @@ decl_module! {
         // default は Rust の仕様ではなくマクロで定義されているもの
         fn deposit_event<T>() = default;
 
+        fn on_initialise(_n: T::BlockNumber) {
+            <IssuesThisBlock<T>>::kill();
+        }
+
+        fn on_finalise(n: T::BlockNumber) {
+            Self::_expire_transfers(n);
+        }
+
         /// オリジナル資産発行（作成）
         /// 関数名は MultiChain に合わせている
         /// name: 資産名
@@ -109,64 +751,140 @@ decl_module! {
             // 関数呼び出し者
             let sender = ensure_signed(origin)?;
 
-            // 発行済資産数
-            let owned_asset_count = Self::owned_asset_count(&sender);
-            // 発行済資産数 + 1
-            let new_owned_asset_count = owned_asset_count.checked_add(1)
-                .ok_or("Overflow adding a new Asset to account balance")?;
+            Self::_issue(sender, name, issue_qty, open)?;
 
-            // 全資産数
-            let all_asset_count = Self::all_asset_count();
-            // 全資産数 + 1
-            let new_all_asset_count = all_asset_count.checked_add(1)
-                .ok_or("Overflow adding a new Asset to total supply")?;
+            Ok(())
+        }
 
-            // 資産 ID 生成
-            let nonce = <Nonce<T>>::get();
-            let random_hash = (<system::Module<T>>::random_seed(), &sender, nonce)
-                .using_encoded(<T as system::Trait>::Hashing::hash);
+        /// 凍結した状態でのオリジナル資産発行
+        /// 公開前の資産向け。unfreeze するまで送信できない
+        fn issue_frozen(origin, name: Vec<u8>, issue_qty: u64, open: bool) -> Result {
+            let sender = ensure_signed(origin)?;
 
-            ensure!(!<AssetOwner<T>>::exists(random_hash), "Asset already exists");
+            let asset_id = Self::_issue(sender, name, issue_qty, open)?;
 
-            // オリジナル資産情報
-            let new_asset = Asset {
-                id: random_hash,
-                name: name,
-                open: open
-            };
+            // --------------------- 更新
+            <FrozenAssets<T>>::insert(asset_id, true);
+            Self::_log_owner_action(asset_id, b"issue_frozen");
+            // --------------------- 更新 --- ここまで
 
-            // 資産発行
-            let my_asset_count = Self::my_asset_count(&sender);
-            let new_my_asset_count = my_asset_count.checked_add(1)
-                .ok_or("Overflow adding a new My Asset to total supply")?;
+            Ok(())
+        }
+
+        /// 焼却できないオリジナル資産発行
+        /// burn / burn_from / 送信時の焼却はできない。open == false であれば発行済量は固定になる
+        fn issue_non_burnable(origin, name: Vec<u8>, issue_qty: u64, open: bool) -> Result {
+            let sender = ensure_signed(origin)?;
 
+            let asset_id = Self::_issue(sender, name, issue_qty, open)?;
 
             // --------------------- 更新
-            // 更新する値が正常であることが確認済みであることが必須!
+            <NonBurnable<T>>::insert(asset_id, true);
+            // --------------------- 更新 --- ここまで
+
+            Ok(())
+        }
+
+        /// 資産名から資産 ID を決めるオリジナル資産発行
+        /// 資産 ID は資産名のハッシュになるため、どの環境でも同じ名前なら同じ ID になる
+        /// 同じ名前の資産が既にある場合は失敗する
+        fn issue_named(origin, name: Vec<u8>, issue_qty: u64, open: bool) -> Result {
+            let sender = ensure_signed(origin)?;
 
-            <Assets<T>>::insert(random_hash, new_asset);
-            <AssetOwner<T>>::insert(random_hash, &sender);
+            let asset_id = <T as system::Trait>::Hashing::hash_of(&name);
+            // ID は発行前に計算できるため、発行前に受け取りを拒否できる
+            ensure!(!Self::asset_opt_out((sender.clone(), asset_id)), "Recipient opted out of this asset");
+            Self::_issue_with_id(sender, asset_id, name, issue_qty, open)
+        }
+
+        /// 初期配布付きのオリジナル資産発行
+        /// 資産を発行し (発行者は呼び出し者)、`distribution` の各アカウントに初期所有量を割り当てる
+        /// 発行量は配布量の合計。発行者自身を含めることもできる
+        /// distribution: (アカウント, 配布量) の配列。重複不可、MAX_BATCH_LEN 件まで
+        fn issue_and_distribute(origin, name: Vec<u8>, open: bool, distribution: Vec<(T::AccountId, u64)>) -> Result {
+            let sender = ensure_signed(origin)?;
 
-            <AllAssetsArray<T>>::insert(all_asset_count, random_hash);
-            <AllAssetsCount<T>>::put(new_all_asset_count);
-            <AllAssetsIndex<T>>::insert(random_hash, all_asset_count);
+            ensure!(!distribution.is_empty(), "Empty batch");
+            ensure!(distribution.len() <= MAX_BATCH_LEN, "Too many entries in distribution");
 
-            <OwnedAssetsArray<T>>::insert((sender.clone(), owned_asset_count), random_hash);
-            <OwnedAssetsCount<T>>::insert(&sender, new_owned_asset_count);
-            <OwnedAssetsIndex<T>>::insert(random_hash, owned_asset_count);
+            // 発行前にすべての配布先を確認する
+            let next_asset_id = Self::_next_asset_id(&sender);
+            let mut total: u64 = 0;
+            for (i, (who, qty)) in distribution.iter().enumerate() {
+                ensure!(!distribution[..i].iter().any(|(prev, _)| prev == who), "Duplicate account in distribution");
+                ensure!(!Self::is_reserved_account(who), "Cannot send to a reserved account");
+                ensure!(!Self::asset_opt_out((who.clone(), next_asset_id)), "Recipient opted out of this asset");
+                total = total.checked_add(*qty).ok_or("Overflow adding the distribution total")?;
+                if *who != sender {
+                    Self::my_asset_count(who).checked_add(1)
+                        .ok_or("Overflow adding a new My Asset to total supply")?;
+                }
+            }
 
-            <TotalIssuedAssets<T>>::insert(random_hash, issue_qty);
+            let asset_id = Self::_issue(sender.clone(), name, total, open)?;
+            debug_assert!(asset_id == next_asset_id, "issued asset id differs from the checked one");
 
-            <Nonce<T>>::mutate(|n| *n += 1);
+            // --------------------- 更新
+            // 発行者が合計を受け取った状態から、各配布先の量に置き換える
+            let own_share = distribution.iter()
+                .find(|(who, _)| *who == sender)
+                .map(|(_, qty)| *qty)
+                .unwrap_or(0);
+            for (who, qty) in distribution.iter() {
+                if *who != sender {
+                    Self::_set_balance(who, asset_id, *qty)?;
+                }
+            }
+            Self::_set_balance(&sender, asset_id, own_share)?;
+            // --------------------- 更新 --- ここまで
+
+            for (who, qty) in distribution.into_iter() {
+                if who != sender {
+                    Self::_deposit_sent_asset(sender.clone(), who, asset_id, qty);
+                }
+            }
+
+            Ok(())
+        }
+
+        /// コレクション発行
+        /// 共通のコレクション名を付けた資産をまとめて発行する
+        /// collection_name: コレクション名 (既存のコレクション名は使えない)
+        /// items: (資産名, 初期発行量) の配列, 追加発行は不可
+        fn issue_collection(origin, collection_name: Vec<u8>, items: Vec<(Vec<u8>, u64)>) -> Result {
+            let sender = ensure_signed(origin)?;
 
-            <MyAssetsArray<T>>::insert((sender.clone(), my_asset_count), random_hash);
-            <MyAssetsCount<T>>::insert(&sender, new_my_asset_count);
-            <MyAssetsIndex<T>>::insert((sender.clone(), random_hash), my_asset_count);
-            <MyAssetBalances<T>>::insert((sender.clone(), random_hash), issue_qty);
+            ensure!(!items.is_empty(), "Collection has no items");
+            ensure!(items.len() <= MAX_BATCH_LEN, "Too many items in collection");
+            ensure!(Self::name_policy().allows(&collection_name), "Collection name contains disallowed characters");
+            ensure!(!<CollectionAssets<T>>::exists(&collection_name), "Collection already exists");
+
+            // 途中で失敗しないよう、発行前にすべての項目を確認する
+            let min_issue_qty = Self::min_issue_qty();
+            let policy = Self::name_policy();
+            for (name, issue_qty) in items.iter() {
+                ensure!(*issue_qty >= min_issue_qty, "Issue quantity is below the minimum");
+                ensure!(policy.allows(name), "Asset name contains disallowed characters");
+            }
+            let n = items.len() as u64;
+            Self::owned_asset_count(&sender).checked_add(n)
+                .ok_or("Overflow adding a new Asset to account balance")?;
+            Self::all_asset_count().checked_add(n)
+                .ok_or("Overflow adding a new Asset to total supply")?;
+            Self::my_asset_count(&sender).checked_add(n)
+                .ok_or("Overflow adding a new My Asset to total supply")?;
+            ensure!(u64::from(Self::issues_this_block()) + n <= u64::from(Self::max_issues_per_block()),
+                "Too many issues in this block");
 
+            // --------------------- 更新
+            let mut asset_ids = Vec::with_capacity(items.len());
+            for (name, issue_qty) in items {
+                let asset_id = Self::_issue(sender.clone(), name, issue_qty, false)?;
+                <Collection<T>>::insert(asset_id, collection_name.clone());
+                asset_ids.push(asset_id);
+            }
+            <CollectionAssets<T>>::insert(&collection_name, asset_ids);
             // --------------------- 更新 --- ここまで
-            
-            Self::deposit_event(RawEvent::Issued(sender, random_hash));
 
             Ok(())
         }
@@ -180,15 +898,16 @@ decl_module! {
             let sender = ensure_signed(origin)?;
 
             // 存在確認
-            ensure!(<Assets<T>>::exists(asset_id), "This asset does not exist");
+            let asset = Self::try_asset(asset_id).ok_or("This asset does not exist")?;
             
             // 所有者（発行者）確認
             let owner = Self::owner_of(asset_id).ok_or("No owner for this asset")?;
             ensure!(owner == sender, "You do not own this asset");
 
             // 追加発行確認
-            let asset = Self::asset(asset_id);
             ensure!(asset.open == true, "You can not issue more");
+            let max_mint = Self::max_mint_per_call(asset_id);
+            ensure!(max_mint == 0 || issue_qty <= max_mint, "Mint exceeds per-call cap");
 
             // 全発行量
             let total_issued_asset = Self::total_issued_asset(asset_id);
@@ -198,21 +917,36 @@ decl_module! {
             // 追加発行した資産を現在の資産に加算
             let my_asset_balance = Self::my_asset_balance((sender.clone(), asset_id));
             let new_my_asset_balance = my_asset_balance.checked_add(issue_qty)
-                .ok_or("Overflow adding a new Asset")?;
+                .ok_or("Overflow adding a new Asset to the owner's balance")?;
 
             // --------------------- 更新
             // 更新する値が正常であることが確認済みであることが必須!
+            Self::_set_balance(&sender, asset_id, new_my_asset_balance)?;
             <TotalIssuedAssets<T>>::insert(asset_id, new_total_issued_asset);
-            <MyAssetBalances<T>>::insert((sender.clone(), asset_id), new_my_asset_balance);
+            Self::_record_supply(asset_id, new_total_issued_asset);
+            Self::_audit(asset_id);
+            Self::_log_owner_action(asset_id, b"issuemore");
 
             // --------------------- 更新 --- ここまで
 
             // イベント
-            Self::deposit_event(RawEvent::IssuedMore(sender, asset_id, issue_qty));
+            let nonce = Self::_next_account_nonce(&sender);
+            Self::_deposit_event(RawEvent::IssuedMore(sender, asset_id, issue_qty, Self::_next_event_seq(), nonce));
 
             Ok(())
         }
 
+        /// issue の別名
+        /// Substrate の慣例 (create / mint) で呼べるようにする
+        fn create(origin, name: Vec<u8>, issue_qty: u64, open: bool) -> Result {
+            Self::issue(origin, name, issue_qty, open)
+        }
+
+        /// issuemore の別名
+        fn mint(origin, asset_id: T::Hash, issue_qty: u64) -> Result {
+            Self::issuemore(origin, asset_id, issue_qty)
+        }
+
         /// 資産送信
         ///
         /// # Arguments
@@ -226,58 +960,6354 @@ decl_module! {
             // 署名確認
             let sender = ensure_signed(origin)?;
 
-            // 所有確認
-            // - 資産確認
-            ensure!(<MyAssetsIndex<T>>::exists((sender.clone(), asset_id)), "This asset does not exist");
-            // - 送信額確認
-            let my_asset_balance = Self::my_asset_balance((sender.clone(), asset_id));
-            ensure!(my_asset_balance >= qty, "Your asset is less than you want to send the amount.");
-            
-            // -- 受信者資産
-            let flg = <MyAssetsIndex<T>>::exists((to.clone(), asset_id));
-            let to_asset_balance = if flg {
-                Self::my_asset_balance((to.clone(), asset_id))
-            } else {
-                0
-            };
+            ensure!(<Assets<T>>::exists(asset_id), "This asset does not exist");
+
+            Self::_sendasset(sender, to, asset_id, qty, false)
+        }
 
-            // 送信者資産
-            let new_my_asset_balance = my_asset_balance.checked_sub(qty)
-                .ok_or("Your asset is less than you want to send the amount.")?;
-            // 受信者資産
-            let new_to_asset_balance = to_asset_balance.checked_add(qty)
-                .ok_or("Overflow adding (to)'s asset")?;
+        /// 受取先付きの資産送信
+        /// 受信者側の制限 (受け取り拒否・許可リスト・送信先制限) で届けられない場合、
+        /// 資産に受取先が設定されていればそちらへ送る。それ以外は sendasset と同じ
+        fn send_asset_or_fallback(origin, to: T::AccountId, asset_id: T::Hash, qty: u64) -> Result {
+            let sender = ensure_signed(origin)?;
 
+            ensure!(<Assets<T>>::exists(asset_id), "This asset does not exist");
 
-            // if 相手が資産を持っていない場合は資産情報を追加
-            if !flg {
-                let to_asset_count = Self::my_asset_count(&to);
-                let new_to_asset_count = to_asset_count.checked_add(1)
-                    .ok_or("Overflow adding a new My Asset to total supply")?;
+            match Self::fallback_account(asset_id) {
+                Some(fallback) if Self::_recipient_rejects(asset_id, &to) => {
+                    Self::_sendasset(sender.clone(), fallback.clone(), asset_id, qty, false)?;
+                    Self::_deposit_event(RawEvent::DeliveredToFallback(sender, to, fallback, asset_id, qty));
+                    Ok(())
+                }
+                _ => Self::_sendasset(sender, to, asset_id, qty, false),
+            }
+        }
 
-            // --------------------- 更新
-            // 更新する値が正常であることが確認済みであることが必須!
+        /// 受取先の設定
+        /// 発行者のみ実行可能。None で解除する
+        fn set_fallback_account(origin, asset_id: T::Hash, fallback: Option<T::AccountId>) -> Result {
+            let sender = ensure_signed(origin)?;
 
-                <MyAssetBalances<T>>::insert((sender.clone(), asset_id), new_my_asset_balance);
+            let owner = Self::owner_of(asset_id).ok_or("No owner for this asset")?;
+            ensure!(owner == sender, "You do not own this asset");
 
+            match fallback {
+                Some(fallback) => {
+                    ensure!(!Self::is_reserved_account(&fallback), "Cannot send to a reserved account");
+                    <FallbackAccount<T>>::insert(asset_id, fallback);
+                }
+                None => <FallbackAccount<T>>::remove(asset_id),
+            }
+            Self::_log_owner_action(asset_id, b"set_fallback_account");
 
-                <MyAssetsArray<T>>::insert((to.clone(), to_asset_count), asset_id);
-                <MyAssetsCount<T>>::insert(&to, new_to_asset_count);
-                <MyAssetsIndex<T>>::insert((to.clone(), asset_id), to_asset_count);
-                <MyAssetBalances<T>>::insert((to.clone(), asset_id), new_to_asset_balance);
-            // --------------------- 更新 --- ここまで
-            } else {
-            // --------------------- 更新
-            // 更新する値が正常であることが確認済みであることが必須!
-                <MyAssetBalances<T>>::insert((sender.clone(), asset_id), new_my_asset_balance);
-                <MyAssetBalances<T>>::insert((to.clone(), asset_id), new_to_asset_balance);
-            // --------------------- 更新 --- ここまで
-            }            
+            Ok(())
+        }
 
-            // イベント
-            Self::deposit_event(RawEvent::SentAsset(sender, to, asset_id, qty));
+        /// 凍結
+        /// 発行者のみ実行可能。凍結中の資産は unfreeze するまで送信できない
+        fn freeze(origin, asset_id: T::Hash) -> Result {
+            let sender = ensure_signed(origin)?;
+
+            let owner = Self::owner_of(asset_id).ok_or("No owner for this asset")?;
+            ensure!(owner == sender, "You do not own this asset");
+
+            <FrozenAssets<T>>::insert(asset_id, true);
+            Self::_log_owner_action(asset_id, b"freeze");
+
+            Ok(())
+        }
+
+        /// 凍結解除
+        /// 発行者のみ実行可能
+        fn unfreeze(origin, asset_id: T::Hash) -> Result {
+            let sender = ensure_signed(origin)?;
+
+            let owner = Self::owner_of(asset_id).ok_or("No owner for this asset")?;
+            ensure!(owner == sender, "You do not own this asset");
+
+            <FrozenAssets<T>>::remove(asset_id);
+            Self::_log_owner_action(asset_id, b"unfreeze");
+
+            Ok(())
+        }
+
+        /// メモ付き資産送信
+        /// sendasset と同じ送信を行い、メモを SentWithMemo イベントに残す
+        /// メモ必須の資産はこちらでしか送信できない
+        fn send_asset_with_memo(origin, to: T::AccountId, asset_id: T::Hash, qty: u64, memo: Vec<u8>) -> Result {
+            let sender = ensure_signed(origin)?;
+
+            ensure!(!memo.is_empty(), "Memo must not be empty");
+            ensure!(memo.len() <= MAX_MEMO_LEN, "Memo is too long");
+
+            Self::_sendasset(sender.clone(), to.clone(), asset_id, qty, true)?;
+
+            Self::_deposit_event(RawEvent::SentWithMemo(sender, to, asset_id, qty, memo));
+
+            Ok(())
+        }
+
+        /// コミュニティ基金の設定
+        /// 発行者のみ実行可能。`fund` が None の場合は分配しない
+        ///
+        /// # Arguments
+        ///
+        /// `asset_id` - 資産 ID
+        /// `fund` - 基金アカウント
+        /// `bps` - 送信量に対する分配率 (万分率)
+        fn set_community_fund(origin, asset_id: T::Hash, fund: Option<T::AccountId>, bps: u16) -> Result {
+            let sender = ensure_signed(origin)?;
+
+            let owner = Self::owner_of(asset_id).ok_or("No owner for this asset")?;
+            ensure!(owner == sender, "You do not own this asset");
+            ensure!(u64::from(bps) <= BPS_DENOMINATOR, "Community share exceeds 100%");
+
+            match fund.clone() {
+                Some(account) => <CommunityFund<T>>::insert(asset_id, account),
+                None => <CommunityFund<T>>::remove(asset_id),
+            }
+            <CommunityBps<T>>::insert(asset_id, bps);
+            Self::_log_owner_action(asset_id, b"set_community_fund");
+
+            Self::_deposit_event(RawEvent::CommunityFundSet(asset_id, fund, bps));
+
+            Ok(())
+        }
+
+        /// 所有者変更の提案
+        /// 所有者のみ実行可能。再度呼ぶと提案を上書きする
+        fn propose_new_owner(origin, asset_id: T::Hash, new_owner: T::AccountId) -> Result {
+            let sender = ensure_signed(origin)?;
+
+            let owner = Self::owner_of(asset_id).ok_or("No owner for this asset")?;
+            ensure!(owner == sender, "You do not own this asset");
+            ensure!(new_owner != sender, "You already own this asset");
+
+            <PendingOwner<T>>::insert(asset_id, new_owner.clone());
+            Self::_log_owner_action(asset_id, b"propose_new_owner");
+
+            Self::_deposit_event(RawEvent::OwnershipProposed(asset_id, new_owner));
+
+            Ok(())
+        }
+
+        /// 所有者変更の受諾
+        /// 提案された所有者のみ実行可能
+        fn accept_ownership(origin, asset_id: T::Hash) -> Result {
+            let sender = ensure_signed(origin)?;
+
+            let pending = Self::pending_owner(asset_id).ok_or("No pending owner for this asset")?;
+            ensure!(pending == sender, "You are not the proposed owner");
+            let owner = Self::owner_of(asset_id).ok_or("No owner for this asset")?;
+
+            Self::_transfer_ownership(asset_id, owner.clone(), sender.clone())?;
+            <PendingOwner<T>>::remove(asset_id);
+            // 前の所有者が始めた変更は引き継がない
+            <OwnerRotations<T>>::remove(asset_id);
+            Self::_log_owner_action(asset_id, b"accept_ownership");
+
+            Self::_deposit_event(RawEvent::OwnershipTransferred(asset_id, owner, sender));
+
+            Ok(())
+        }
+
+        /// 猶予付きの所有者変更の開始
+        /// 所有者のみ実行可能。鍵の漏洩に備えた変更で、新しい所有者の受諾は不要
+        /// owner_rotation_delay ブロック後から finalize_owner_rotation で確定でき、それまでは cancel_owner_rotation で取り消せる
+        /// 再度呼ぶと変更先を上書きし、猶予もやり直す
+        fn begin_owner_rotation(origin, asset_id: T::Hash, new_owner: T::AccountId) -> Result {
+            let sender = ensure_signed(origin)?;
+
+            let owner = Self::owner_of(asset_id).ok_or("No owner for this asset")?;
+            ensure!(owner == sender, "You do not own this asset");
+            ensure!(new_owner != sender, "You already own this asset");
+            let effective_at = <system::Module<T>>::block_number().checked_add(&Self::owner_rotation_delay())
+                .ok_or("Overflow computing the rotation block")?;
+
+            <OwnerRotations<T>>::insert(asset_id, (new_owner.clone(), effective_at));
+            Self::_log_owner_action(asset_id, b"begin_owner_rotation");
+
+            Self::_deposit_event(RawEvent::OwnerRotationBegun(asset_id, new_owner, effective_at));
+
+            Ok(())
+        }
+
+        /// 猶予付きの所有者変更の取り消し
+        /// 所有者のみ実行可能
+        fn cancel_owner_rotation(origin, asset_id: T::Hash) -> Result {
+            let sender = ensure_signed(origin)?;
+
+            let owner = Self::owner_of(asset_id).ok_or("No owner for this asset")?;
+            ensure!(owner == sender, "You do not own this asset");
+            ensure!(<OwnerRotations<T>>::exists(asset_id), "No owner rotation for this asset");
+
+            <OwnerRotations<T>>::remove(asset_id);
+            Self::_log_owner_action(asset_id, b"cancel_owner_rotation");
+
+            Self::_deposit_event(RawEvent::OwnerRotationCancelled(asset_id));
 
             Ok(())
         }
+
+        /// 猶予付きの所有者変更の確定
+        /// 現在の所有者か新しい所有者のみ実行可能。猶予が過ぎるまでは失敗する
+        fn finalize_owner_rotation(origin, asset_id: T::Hash) -> Result {
+            let sender = ensure_signed(origin)?;
+
+            let (new_owner, effective_at) = Self::owner_rotation(asset_id).ok_or("No owner rotation for this asset")?;
+            let owner = Self::owner_of(asset_id).ok_or("No owner for this asset")?;
+            ensure!(sender == owner || sender == new_owner, "You are not a party to this rotation");
+            ensure!(<system::Module<T>>::block_number() >= effective_at, "Owner rotation is still timelocked");
+
+            Self::_transfer_ownership(asset_id, owner.clone(), new_owner.clone())?;
+            <OwnerRotations<T>>::remove(asset_id);
+            // 前の所有者の提案は引き継がない
+            <PendingOwner<T>>::remove(asset_id);
+            Self::_log_owner_action(asset_id, b"finalize_owner_rotation");
+
+            Self::_deposit_event(RawEvent::OwnershipTransferred(asset_id, owner, new_owner));
+
+            Ok(())
+        }
+
+        /// 保有者数の上限の設定
+        /// 発行者のみ実行可能 (0 で無制限)
+        /// 新しい保有者を増やす送信だけが制限され、既存の保有者への送信は影響を受けない
+        fn set_max_holders(origin, asset_id: T::Hash, max: u64) -> Result {
+            let sender = ensure_signed(origin)?;
+
+            let owner = Self::owner_of(asset_id).ok_or("No owner for this asset")?;
+            ensure!(owner == sender, "You do not own this asset");
+
+            <MaxHolders<T>>::insert(asset_id, max);
+            Self::_log_owner_action(asset_id, b"set_max_holders");
+
+            Ok(())
+        }
+
+        /// 1 回の追加発行量の上限の設定
+        /// 発行者のみ実行可能 (0 で無制限)
+        /// 累計の発行量ではなく、issuemore 1 回あたりの量を制限する
+        fn set_max_mint_per_call(origin, asset_id: T::Hash, max: u64) -> Result {
+            let sender = ensure_signed(origin)?;
+
+            let owner = Self::owner_of(asset_id).ok_or("No owner for this asset")?;
+            ensure!(owner == sender, "You do not own this asset");
+
+            <MaxMintPerCall<T>>::insert(asset_id, max);
+            Self::_log_owner_action(asset_id, b"set_max_mint_per_call");
+
+            Ok(())
+        }
+
+        /// 送信時の焼却率の設定
+        /// 発行者のみ実行可能 (万分率, 0 で焼却しない)
+        /// sendasset のたびに送信量の `bps` / 10000 を焼却し、発行済量を減らす
+        fn set_burn_bps(origin, asset_id: T::Hash, bps: u16) -> Result {
+            let sender = ensure_signed(origin)?;
+
+            let owner = Self::owner_of(asset_id).ok_or("No owner for this asset")?;
+            ensure!(owner == sender, "You do not own this asset");
+            ensure!(u64::from(bps) <= BPS_DENOMINATOR, "Burn share exceeds 100%");
+            ensure!(bps == 0 || Self::is_burnable(asset_id), "Asset is not burnable");
+
+            <BurnBps<T>>::insert(asset_id, bps);
+            Self::_log_owner_action(asset_id, b"set_burn_bps");
+
+            Ok(())
+        }
+
+        /// メモ必須の切り替え
+        /// 発行者のみ実行可能
+        /// 必須にすると sendasset / send_asset_batch / transfer_from では送信できなくなる
+        fn set_require_memo(origin, asset_id: T::Hash, required: bool) -> Result {
+            let sender = ensure_signed(origin)?;
+
+            let owner = Self::owner_of(asset_id).ok_or("No owner for this asset")?;
+            ensure!(owner == sender, "You do not own this asset");
+
+            <RequireMemo<T>>::insert(asset_id, required);
+            Self::_log_owner_action(asset_id, b"set_require_memo");
+
+            Ok(())
+        }
+
+        /// 委員会の設定
+        /// 発行者のみ実行可能。`members` が空の場合は委員会を解散する
+        /// 委員会は発行者に代わって AdminAction を実行できる (発行者自身の権限はそのまま)
+        fn set_committee(origin, asset_id: T::Hash, members: Vec<T::AccountId>, threshold: u32) -> Result {
+            let sender = ensure_signed(origin)?;
+
+            let owner = Self::owner_of(asset_id).ok_or("No owner for this asset")?;
+            ensure!(owner == sender, "You do not own this asset");
+
+            if members.is_empty() {
+                <Committee<T>>::remove(asset_id);
+            } else {
+                ensure!(members.len() <= MAX_COMMITTEE_LEN, "Too many committee members");
+                for (i, member) in members.iter().enumerate() {
+                    ensure!(!members[..i].contains(member), "Duplicate committee member");
+                }
+                ensure!(threshold >= 1 && threshold as usize <= members.len(),
+                    "Threshold must be between 1 and the committee size");
+                <Committee<T>>::insert(asset_id, (members, threshold));
+            }
+            Self::_log_owner_action(asset_id, b"set_committee");
+
+            Ok(())
+        }
+
+        /// 委員会操作の提案
+        /// 委員のみ実行可能。提案者の承認を含み、必要数に達していればすぐに実行する
+        fn propose_admin_action(origin, asset_id: T::Hash, action: AdminAction) -> Result {
+            let sender = ensure_signed(origin)?;
+
+            let (members, threshold) = Self::committee(asset_id);
+            ensure!(members.contains(&sender), "You are not a committee member");
+
+            let nonce = <Nonce<T>>::get();
+            let proposal_id = (<system::Module<T>>::random_seed(), &sender, nonce)
+                .using_encoded(<T as system::Trait>::Hashing::hash);
+            ensure!(!<AdminProposals<T>>::exists(proposal_id), "Admin proposal already exists");
+
+            let proposal = AdminProposal {
+                asset_id,
+                action,
+                approvals: vec![sender.clone()],
+            };
+
+            if threshold <= 1 {
+                Self::_execute_admin_action(&proposal)?;
+                <Nonce<T>>::mutate(|n| *n += 1);
+                Self::_deposit_event(RawEvent::AdminActionProposed(proposal_id, asset_id, sender));
+                Self::_deposit_event(RawEvent::AdminActionExecuted(proposal_id));
+                return Ok(());
+            }
+
+            // --------------------- 更新
+            <AdminProposals<T>>::insert(proposal_id, proposal);
+            <Nonce<T>>::mutate(|n| *n += 1);
+            // --------------------- 更新 --- ここまで
+
+            Self::_deposit_event(RawEvent::AdminActionProposed(proposal_id, asset_id, sender));
+
+            Ok(())
+        }
+
+        /// 委員会操作の承認
+        /// 委員のみ実行可能。承認数が必要数に達した時点で実行し、提案を削除する
+        fn approve_admin_action(origin, proposal_id: T::Hash) -> Result {
+            let sender = ensure_signed(origin)?;
+
+            let mut proposal = Self::admin_proposal(proposal_id).ok_or("This admin proposal does not exist")?;
+            let (members, threshold) = Self::committee(proposal.asset_id);
+            ensure!(members.contains(&sender), "You are not a committee member");
+            ensure!(!proposal.approvals.contains(&sender), "You have already approved this proposal");
+
+            proposal.approvals.push(sender.clone());
+            // 提案後に委員から外れた者の承認は数えない
+            let approved = proposal.approvals.iter().filter(|a| members.contains(a)).count();
+
+            if approved >= threshold as usize {
+                Self::_execute_admin_action(&proposal)?;
+                <AdminProposals<T>>::remove(proposal_id);
+                Self::_deposit_event(RawEvent::AdminActionApproved(proposal_id, sender));
+                Self::_deposit_event(RawEvent::AdminActionExecuted(proposal_id));
+            } else {
+                <AdminProposals<T>>::insert(proposal_id, proposal);
+                Self::_deposit_event(RawEvent::AdminActionApproved(proposal_id, sender));
+            }
+
+            Ok(())
+        }
+
+        /// 送信間隔の設定
+        /// 発行者のみ実行可能
+        /// 同じアカウントが同じ資産を送信するには `blocks` ブロック空ける必要がある (0 で無制限)
+        fn set_cooldown(origin, asset_id: T::Hash, blocks: T::BlockNumber) -> Result {
+            let sender = ensure_signed(origin)?;
+
+            let owner = Self::owner_of(asset_id).ok_or("No owner for this asset")?;
+            ensure!(owner == sender, "You do not own this asset");
+
+            <Cooldown<T>>::insert(asset_id, blocks);
+            Self::_log_owner_action(asset_id, b"set_cooldown");
+
+            Ok(())
+        }
+
+        /// 送信イベントの有効・無効の切り替え
+        /// 発行者のみ実行可能
+        /// 無効にすると SentAsset は発行されなくなる (イベント番号は進む)
+        fn set_events_enabled(origin, asset_id: T::Hash, enabled: bool) -> Result {
+            let sender = ensure_signed(origin)?;
+
+            let owner = Self::owner_of(asset_id).ok_or("No owner for this asset")?;
+            ensure!(owner == sender, "You do not own this asset");
+
+            <EventsEnabled<T>>::insert(asset_id, enabled);
+            Self::_log_owner_action(asset_id, b"set_events_enabled");
+
+            Ok(())
+        }
+
+        /// 許可リスト制の切り替え
+        /// 発行者のみ実行可能
+        fn set_requires_allowlist(origin, asset_id: T::Hash, required: bool) -> Result {
+            let sender = ensure_signed(origin)?;
+
+            let owner = Self::owner_of(asset_id).ok_or("No owner for this asset")?;
+            ensure!(owner == sender, "You do not own this asset");
+
+            <RequiresAllowlist<T>>::insert(asset_id, required);
+            Self::_log_owner_action(asset_id, b"set_requires_allowlist");
+
+            Ok(())
+        }
+
+        /// 許可リストへの追加・削除
+        /// 発行者のみ実行可能
+        fn set_allowlisted(origin, asset_id: T::Hash, who: T::AccountId, allowed: bool) -> Result {
+            let sender = ensure_signed(origin)?;
+
+            let owner = Self::owner_of(asset_id).ok_or("No owner for this asset")?;
+            ensure!(owner == sender, "You do not own this asset");
+
+            if allowed {
+                <Allowlisted<T>>::insert((asset_id, who), true);
+            } else {
+                <Allowlisted<T>>::remove((asset_id, who));
+            }
+            Self::_log_owner_action(asset_id, b"set_allowlisted");
+
+            Ok(())
+        }
+
+        /// 送信先制限の切り替え
+        /// 発行者のみ実行可能
+        fn set_restrict_destinations(origin, asset_id: T::Hash, restricted: bool) -> Result {
+            let sender = ensure_signed(origin)?;
+
+            let owner = Self::owner_of(asset_id).ok_or("No owner for this asset")?;
+            ensure!(owner == sender, "You do not own this asset");
+
+            <RestrictDestinations<T>>::insert(asset_id, restricted);
+            Self::_log_owner_action(asset_id, b"set_restrict_destinations");
+
+            Ok(())
+        }
+
+        /// 許可された送信先への追加・削除
+        /// 発行者のみ実行可能
+        fn set_allowed_destination(origin, asset_id: T::Hash, destination: T::AccountId, allowed: bool) -> Result {
+            let sender = ensure_signed(origin)?;
+
+            let owner = Self::owner_of(asset_id).ok_or("No owner for this asset")?;
+            ensure!(owner == sender, "You do not own this asset");
+
+            if allowed {
+                <AllowedDestinations<T>>::insert((asset_id, destination), true);
+            } else {
+                <AllowedDestinations<T>>::remove((asset_id, destination));
+            }
+            Self::_log_owner_action(asset_id, b"set_allowed_destination");
+
+            Ok(())
+        }
+
+        /// 全資産の移動 (アカウントの引き払い用)
+        /// 所有資産配列の先頭から MAX_LIST_LEN 件を読み、所有量のある資産を MAX_BATCH_LEN 件まで全量 `to` へ送る
+        /// 送った資産の所有量は 0 になるため、残りがあれば再度呼び出すと続きから送られる
+        /// 凍結・許可リスト・送信先制限・受け取り拒否・送信間隔・メモ必須・投票ロックで送れない資産は飛ばして残す
+        /// それ以外の理由 (保有者数の上限・焼却不可など) で送れない資産があれば、どの資産も送らずに失敗する
+        /// sendasset と同じく、コミュニティ基金への分配と焼却を差し引いた残りが `to` へ届く
+        /// 読んだ範囲で所有量が 0 になった資産は、MyAssets* と保有者配列の登録も取り除く
+        fn exit_all(origin, to: T::AccountId) -> Result {
+            let sender = ensure_signed(origin)?;
+
+            ensure!(to != sender, "You can not exit to yourself");
+            ensure!(!Self::is_reserved_account(&to), "Cannot send to a reserved account");
+
+            let end = rstd::cmp::min(Self::my_asset_count(&sender), MAX_LIST_LEN);
+            // 送る資産と量
+            let mut moving = Vec::new();
+            // 所有量が 0 の資産 (登録は読み終えてから取り除く)
+            let mut emptied = Vec::new();
+            for i in 0..end {
+                if moving.len() >= MAX_BATCH_LEN {
+                    break;
+                }
+                let asset_id = Self::my_asset_by_index((sender.clone(), i));
+                let balance = Self::my_asset_balance((sender.clone(), asset_id));
+                if balance == 0 {
+                    emptied.push(asset_id);
+                    continue;
+                }
+                if !Self::_can_exit(&sender, &to, asset_id) {
+                    continue;
+                }
+                // 途中の資産で失敗して一部だけ送信されないよう、更新前にすべて確認する
+                Self::_ensure_can_send_with_deductions(&sender, &to, asset_id, balance)?;
+                moving.push((asset_id, balance));
+            }
+            ensure!(!moving.is_empty(), "No assets to move");
+
+            // --------------------- 更新
+            for (asset_id, balance) in moving {
+                let (net, burn) = Self::_send_with_deductions(&sender, &to, asset_id, balance)?;
+                Self::_record_send(&sender, asset_id);
+                Self::_deposit_sent_with_burn(sender.clone(), to.clone(), asset_id, net, burn);
+                emptied.push(asset_id);
+            }
+            for asset_id in emptied {
+                Self::_remove_my_asset(&sender, asset_id);
+            }
+            // --------------------- 更新 --- ここまで
+
+            Ok(())
+        }
+
+        /// 資産の一括送信
+        /// 同じ送信先が複数回含まれる場合はエラーとする
+        /// (量を合算せず、呼び出し側で 1 件にまとめてもらう)
+        ///
+        /// # Arguments
+        ///
+        /// `asset_id` - 資産 ID
+        /// `transfers` - (送信先アドレス, 送信量) の配列
+        /// `summarize` - true の場合、送信先ごとの SentAsset の代わりに BatchSent を 1 件だけ発行する
+        ///   インデクサは送信先ごとの量をイベントから追えなくなり、イベント番号も進まない
+        ///   BatchSent の総量は控除前の量。コミュニティ基金への分配と焼却があれば、
+        ///   それぞれの合計を CommunityFunded 1 件と Burned 1 件で発行する
+        ///
+        /// 各送信では sendasset と同じく、コミュニティ基金への分配と焼却を差し引く
+        fn send_asset_batch(origin, asset_id: T::Hash, transfers: Vec<(T::AccountId, u64)>, summarize: bool) -> Result {
+            let sender = ensure_signed(origin)?;
+
+            ensure!(!transfers.is_empty(), "Empty batch");
+            ensure!(transfers.len() <= MAX_BATCH_LEN, "Too many transfers in batch");
+            ensure!(<Assets<T>>::exists(asset_id), "This asset does not exist");
+
+            // 送信先の重複と送信ポリシーの確認
+            for (i, (to, _)) in transfers.iter().enumerate() {
+                ensure!(!transfers[..i].iter().any(|(prev, _)| prev == to), "Duplicate recipient in batch");
+                Self::_ensure_transfer_allowed(&sender, to, asset_id, false)?;
+            }
+
+            // 送信総量と受信者資産の確認
+            // 途中で失敗して一部だけ送信されないよう、更新前にすべて確認する
+            Self::_ensure_registered_holder(&sender, asset_id)?;
+            let mut total: u64 = 0;
+            let mut fund_total: u64 = 0;
+            // _send_with_deductions で送る順の (送信先, 量)。保有者数の上限をまとめて確認する
+            let mut legs = Vec::new();
+            for (to, qty) in transfers.iter() {
+                total = total.checked_add(*qty).ok_or("Overflow adding the batch total")?;
+                if *to != sender {
+                    Self::my_asset_balance((to.clone(), asset_id)).checked_add(*qty)
+                        .ok_or("Overflow adding (to)'s asset")?;
+                }
+                let (split, burn) = Self::_transfer_deductions(&sender, to, asset_id, *qty)?;
+                let cut = split.as_ref().map_or(0, |(_, cut)| *cut);
+                legs.push((to.clone(), *qty - cut - burn));
+                if let Some((fund, cut)) = split {
+                    fund_total = fund_total.checked_add(cut).ok_or("Overflow adding the community fund's asset")?;
+                    legs.push((fund, cut));
+                }
+                if burn > 0 {
+                    ensure!(Self::is_burnable(asset_id), "Asset is not burnable");
+                }
+            }
+            if let Some(fund) = Self::community_fund(asset_id) {
+                if fund != sender {
+                    Self::my_asset_balance((fund, asset_id)).checked_add(fund_total)
+                        .ok_or("Overflow adding the community fund's asset")?;
+                }
+            }
+            ensure!(Self::my_asset_balance((sender.clone(), asset_id)) >= total,
+                "Your asset is less than you want to send the amount.");
+            ensure!(Self::spendable_balance(&sender, asset_id) >= total, "Your asset is locked for voting");
+            Self::_ensure_holder_capacity(&sender, asset_id, &legs)?;
+
+            let count = transfers.len() as u64;
+            // 焼却量の合計は total を超えない
+            let mut burned: u64 = 0;
+            for (to, qty) in transfers.into_iter() {
+                if summarize {
+                    let (_, _, burn) = Self::_move_with_deductions(&sender, &to, asset_id, qty)?;
+                    burned += burn;
+                } else {
+                    let (net, burn) = Self::_send_with_deductions(&sender, &to, asset_id, qty)?;
+                    Self::_deposit_sent_with_burn(sender.clone(), to, asset_id, net, burn);
+                }
+            }
+            Self::_record_send(&sender, asset_id);
+
+            if summarize {
+                if Self::events_enabled(asset_id) {
+                    Self::_deposit_event(RawEvent::BatchSent(asset_id, sender.clone(), count, total));
+                }
+                // 分配の合計は更新前の確認で求めた fund_total と同じ
+                if let Some(fund) = Self::community_fund(asset_id) {
+                    if fund_total > 0 {
+                        Self::_deposit_event(RawEvent::CommunityFunded(sender.clone(), fund, asset_id, fund_total));
+                    }
+                }
+                if burned > 0 {
+                    let nonce = Self::_next_account_nonce(&sender);
+                    Self::_deposit_event(RawEvent::Burned(sender, asset_id, burned, Self::_next_event_seq(), nonce));
+                }
+            }
+
+            Ok(())
+        }
+
+        /// 資産焼却
+        /// 自分の資産を焼却し、発行済量を減らす
+        fn burn(origin, asset_id: T::Hash, qty: u64) -> Result {
+            let sender = ensure_signed(origin)?;
+
+            Self::_burn(sender.clone(), asset_id, qty)?;
+
+            let nonce = Self::_next_account_nonce(&sender);
+            Self::_deposit_event(RawEvent::Burned(sender, asset_id, qty, Self::_next_event_seq(), nonce));
+
+            Ok(())
+        }
+
+        /// 使用許可量の設定
+        /// `spender` が自分の資産を `qty` まで扱えるようにする (上書き)
+        fn approve(origin, spender: T::AccountId, asset_id: T::Hash, qty: u64) -> Result {
+            let sender = ensure_signed(origin)?;
+
+            ensure!(<Assets<T>>::exists(asset_id), "This asset does not exist");
+            ensure!(sender != spender, "You can not approve yourself");
+            if qty > 0 && Self::allowance((sender.clone(), spender.clone(), asset_id)) == 0 {
+                ensure!(Self::spender_approvals(&spender).len() < MAX_SPENDER_APPROVALS,
+                    "Too many approvals for this spender");
+            }
+
+            Self::_set_allowance(&sender, &spender, asset_id, qty);
+
+            Self::_deposit_event(RawEvent::Approved(sender, spender, asset_id, qty));
+
+            Ok(())
+        }
+
+        /// 使用許可に基づく資産焼却
+        /// 呼び出し者は `from` から許可された量の範囲で焼却できる
+        ///
+        /// # Arguments
+        ///
+        /// `from` - 焼却される資産の所有者
+        /// `asset_id` - 資産 ID
+        /// `qty` - 焼却量
+        fn burn_from(origin, from: T::AccountId, asset_id: T::Hash, qty: u64) -> Result {
+            let sender = ensure_signed(origin)?;
+
+            // 許可量確認
+            let allowance = Self::allowance((from.clone(), sender.clone(), asset_id));
+            let new_allowance = allowance.checked_sub(qty)
+                .ok_or("Allowance is less than you want to burn")?;
+
+            Self::_burn(from.clone(), asset_id, qty)?;
+
+            // --------------------- 更新
+            Self::_set_allowance(&from, &sender, asset_id, new_allowance);
+            // --------------------- 更新 --- ここまで
+
+            let nonce = Self::_next_account_nonce(&from);
+            Self::_deposit_event(RawEvent::Burned(from.clone(), asset_id, qty, Self::_next_event_seq(), nonce));
+            Self::_deposit_event(RawEvent::Approved(from, sender, asset_id, new_allowance));
+
+            Ok(())
+        }
+
+        // ----------- ERC-20 互換
+        // Ethereum から移植するツール向けに ERC-20 の関数名で呼べるようにする
+        // 引数の順序は ERC-20 に合わせ、資産 ID を先頭に置く
+        // approve は既存のもの (spender, asset_id, qty) をそのまま使う
+
+        /// 資産送信 (ERC-20 transfer)
+        /// sendasset と同じ
+        fn transfer(origin, asset_id: T::Hash, to: T::AccountId, qty: u64) -> Result {
+            Self::sendasset(origin, to, asset_id, qty)
+        }
+
+        /// 許可量の範囲での代理送信 (ERC-20 transferFrom)
+        /// sendasset と同じく、コミュニティ基金への分配と焼却を差し引いた残りが `to` へ届く
+        /// 許可量は控除前の `qty` だけ減る
+        fn transfer_from(origin, asset_id: T::Hash, from: T::AccountId, to: T::AccountId, qty: u64) -> Result {
+            let sender = ensure_signed(origin)?;
+
+            ensure!(<Assets<T>>::exists(asset_id), "This asset does not exist");
+            Self::_ensure_transfer_allowed(&from, &to, asset_id, false)?;
+
+            // 許可量確認
+            let allowance = Self::allowance((from.clone(), sender.clone(), asset_id));
+            let new_allowance = allowance.checked_sub(qty)
+                .ok_or("Allowance is less than you want to send")?;
+
+            let (net, burn) = Self::_send_with_deductions(&from, &to, asset_id, qty)?;
+
+            // --------------------- 更新
+            Self::_set_allowance(&from, &sender, asset_id, new_allowance);
+            Self::_record_send(&from, asset_id);
+            // --------------------- 更新 --- ここまで
+
+            Self::_deposit_sent_with_burn(from.clone(), to, asset_id, net, burn);
+            Self::_deposit_event(RawEvent::Approved(from, sender, asset_id, new_allowance));
+
+            Ok(())
+        }
+
+        // ----------- ERC-20 互換 --- ここまで
+
+        /// 中継者に手数料を払う資産送信
+        /// 署名者は `to` へ資産を送り、同時に `relayer` へネイティブトークンで `relayer_fee` を払う
+        /// 片方だけが実行されないよう、資産の送信を確認してから手数料を払い、その後に資産を送る
+        fn sponsored_transfer(origin, to: T::AccountId, asset_id: T::Hash, qty: u64, relayer: T::AccountId, relayer_fee: T::Balance) -> Result {
+            let sender = ensure_signed(origin)?;
+
+            ensure!(relayer != sender, "You can not be your own relayer");
+            ensure!(<Assets<T>>::exists(asset_id), "This asset does not exist");
+            Self::_ensure_can_sendasset(&sender, &to, asset_id, qty, false)?;
+
+            // 手数料の支払い確認
+            // 新しいアカウントの作成は扱わず、既存の中継者にのみ払う
+            let relayer_balance = <balances::Module<T>>::free_balance(&relayer);
+            ensure!(!relayer_balance.is_zero(), "Relayer account does not exist");
+            relayer_balance.checked_add(&relayer_fee).ok_or("Overflow adding the relayer fee")?;
+            let liability = relayer_fee.checked_add(&<balances::Module<T>>::transfer_fee())
+                .ok_or("Overflow adding the relayer fee")?;
+            ensure!(<balances::Module<T>>::free_balance(&sender) >= liability,
+                "Insufficient native balance for the relayer fee");
+
+            // --------------------- 更新
+            // 手数料を先に払う。make_transfer は権利確定前の残高やロック (EnsureAccountLiquid) で払えない場合も、
+            // 更新前に失敗する。資産はまだ動いていない
+            <balances::Module<T>>::make_transfer(&sender, &relayer, relayer_fee)?;
+            // 資産の送信は確認済みのため失敗しない
+            Self::_sendasset(sender, to, asset_id, qty, false)?;
+            // --------------------- 更新 --- ここまで
+
+            Ok(())
+        }
+
+        /// 請求書 ID 付きの資産送信
+        /// sendasset と同じ送信を行い、支払いを請求書 ID で後から参照できるよう記録する
+        /// 同じ請求書 ID は 1 回しか使えない
+        fn pay_invoice(origin, to: T::AccountId, asset_id: T::Hash, qty: u64, invoice_id: T::Hash) -> Result {
+            let sender = ensure_signed(origin)?;
+
+            ensure!(!<Invoices<T>>::exists(invoice_id), "This invoice has already been paid");
+
+            Self::sendasset(system::RawOrigin::Signed(sender.clone()).into(), to.clone(), asset_id, qty)?;
+
+            // --------------------- 更新
+            let now = <system::Module<T>>::block_number();
+            <Invoices<T>>::insert(invoice_id, (sender.clone(), to.clone(), asset_id, qty, now));
+            // --------------------- 更新 --- ここまで
+
+            Self::_deposit_event(RawEvent::InvoicePaid(invoice_id, sender, to, asset_id, qty));
+
+            Ok(())
+        }
+
+        /// 資産の受け取り拒否
+        /// 以後、この資産を sendasset / send_asset_batch で受け取らない
+        /// 資産の存在は確認しないため、発行前の ID (issue_named の資産名のハッシュなど) も指定でき、
+        /// issue_named・issue_and_distribute での発行時の受け取りも拒否する
+        /// ジェネシスの資産は、拒否より前に割り当て済みになる
+        fn opt_out(origin, asset_id: T::Hash) -> Result {
+            let sender = ensure_signed(origin)?;
+
+            <AssetOptOut<T>>::insert((sender, asset_id), true);
+
+            Ok(())
+        }
+
+        /// 資産の受け取り拒否の解除
+        fn opt_in(origin, asset_id: T::Hash) -> Result {
+            let sender = ensure_signed(origin)?;
+
+            <AssetOptOut<T>>::remove((sender, asset_id));
+
+            Ok(())
+        }
+
+        /// 投票のための資産ロック
+        /// `until` ブロックまで `amount` を送信・焼却できなくする
+        /// 既にロックがある場合は量・期限とも延長のみ可能
+        fn lock_for_vote(origin, asset_id: T::Hash, amount: u64, until: T::BlockNumber) -> Result {
+            let sender = ensure_signed(origin)?;
+
+            ensure!(<MyAssetsIndex<T>>::exists((sender.clone(), asset_id)), "This asset does not exist");
+            ensure!(Self::my_asset_balance((sender.clone(), asset_id)) >= amount,
+                "Your asset is less than you want to lock");
+            ensure!(until > <system::Module<T>>::block_number(), "Vote lock must end in the future");
+
+            let key = (sender.clone(), asset_id);
+            if <VoteLocks<T>>::exists(&key) {
+                let (locked, locked_until) = Self::vote_lock(&key);
+                ensure!(amount >= locked && until >= locked_until, "Vote lock can only be extended");
+            }
+
+            <VoteLocks<T>>::insert(&key, (amount, until));
+
+            Self::_deposit_event(RawEvent::VoteLocked(sender, asset_id, amount, until));
+
+            Ok(())
+        }
+
+        /// 投票ロックの解除
+        /// 解除可能ブロックに達していれば実行できる
+        fn unlock_vote(origin, asset_id: T::Hash) -> Result {
+            let sender = ensure_signed(origin)?;
+
+            let key = (sender.clone(), asset_id);
+            ensure!(<VoteLocks<T>>::exists(&key), "No vote lock for this asset");
+            let (_, until) = Self::vote_lock(&key);
+            ensure!(<system::Module<T>>::block_number() >= until, "Vote lock has not expired");
+
+            <VoteLocks<T>>::remove(&key);
+
+            Self::_deposit_event(RawEvent::VoteUnlocked(sender, asset_id));
+
+            Ok(())
+        }
+
+        /// 最小発行量の変更
+        /// sudo (root) のみ実行可能
+        fn set_min_issue_qty(origin, qty: u64) -> Result {
+            ensure_root(origin)?;
+
+            <MinIssueQty<T>>::put(qty);
+
+            Ok(())
+        }
+
+        /// 1 ブロックあたりの発行数上限の変更
+        /// sudo (root) のみ実行可能
+        fn set_max_issues_per_block(origin, max: u32) -> Result {
+            ensure_root(origin)?;
+
+            <MaxIssuesPerBlock<T>>::put(max);
+
+            Ok(())
+        }
+
+        /// 厳格モードの変更
+        /// sudo (root) のみ実行可能
+        /// 制限するのは sendasset (と同じ処理を使う送信) のみ。send_asset_batch や exit_all など
+        /// 件数の上限を持つ一括処理は、複数の保有者を作ることを前提としているため対象外
+        fn set_strict_holder_mode(origin, strict: bool) -> Result {
+            ensure_root(origin)?;
+
+            <StrictHolderMode<T>>::put(strict);
+
+            Ok(())
+        }
+
+        /// イベントの出力レベルの変更
+        /// sudo (root) のみ実行可能
+        fn set_event_verbosity(origin, level: u8) -> Result {
+            ensure_root(origin)?;
+
+            ensure!(level <= EVENTS_DETAILED, "Unknown event verbosity");
+            <EventVerbosity<T>>::put(level);
+
+            Ok(())
+        }
+
+        /// 保有者数の再計算 (移行用)
+        /// sudo (root) のみ実行可能
+        /// HolderCount 導入前の資産は保有者数が 0 のままのため、保有者配列から数え直す
+        /// 1 回に扱える資産は MAX_BATCH_LEN 件、資産ごとの保有者は MAX_VERIFY_HOLDERS 件まで
+        /// 何度実行しても結果は同じなので、資産を分けて複数ブロックで実行できる
+        fn migrate_holder_count(origin, asset_ids: Vec<T::Hash>) -> Result {
+            ensure_root(origin)?;
+
+            ensure!(!asset_ids.is_empty(), "Empty batch");
+            ensure!(asset_ids.len() <= MAX_BATCH_LEN, "Too many assets to migrate at once");
+            for asset_id in asset_ids.iter() {
+                ensure!(Self::asset_holders_count(*asset_id) <= MAX_VERIFY_HOLDERS, "Too many holders to migrate");
+            }
+
+            // --------------------- 更新
+            for asset_id in asset_ids.into_iter() {
+                let holding = (0..Self::asset_holders_count(asset_id))
+                    .filter(|i| {
+                        let who = Self::asset_holder_by_index((asset_id, *i));
+                        Self::my_asset_balance((who, asset_id)) > 0
+                    })
+                    .count() as u64;
+                <HolderCount<T>>::insert(asset_id, holding);
+            }
+            // --------------------- 更新 --- ここまで
+
+            Ok(())
+        }
+
+        /// 所有資産インデックスの修復
+        /// sudo (root) のみ実行可能
+        /// `account` の MyAssetsIndex を、正とする MyAssetsArray / MyAssetsCount から書き直す
+        /// 配列にない資産を指す古いエントリは、資産 ID が分からないため消せない
+        /// 所有資産数が MAX_LIST_LEN を超えるアカウントは扱わない
+        fn rebuild_account_index(origin, account: T::AccountId) -> Result {
+            ensure_root(origin)?;
+
+            let count = Self::my_asset_count(&account);
+            ensure!(count <= MAX_LIST_LEN, "Too many assets to rebuild");
+
+            // --------------------- 更新
+            for i in 0..count {
+                let asset_id = Self::my_asset_by_index((account.clone(), i));
+                <MyAssetsIndex<T>>::insert((account.clone(), asset_id), i);
+            }
+            // --------------------- 更新 --- ここまで
+
+            Ok(())
+        }
+
+        /// スナップショットからの所有量の復元 (チェーン移行用)
+        /// sudo (root) のみ実行可能
+        /// 資産の所有量をすべて `snapshot` の内容で置き換え、発行済量を合計に合わせる
+        /// 載っていない保有者の所有量は 0 になる。保有者配列・投票ロック・使用許可量はそのまま残す
+        /// スナップショット・既存の保有者配列とも MAX_VERIFY_HOLDERS 件まで
+        fn import_snapshot(origin, asset_id: T::Hash, snapshot: Vec<(T::AccountId, u64)>) -> Result {
+            ensure_root(origin)?;
+
+            ensure!(<Assets<T>>::exists(asset_id), "This asset does not exist");
+            ensure!(snapshot.len() as u64 <= MAX_VERIFY_HOLDERS, "Snapshot is too large");
+            ensure!(Self::asset_holders_count(asset_id) <= MAX_VERIFY_HOLDERS, "Too many holders to import over");
+
+            // 合計と登録数を更新前に確認する
+            // 保有者配列の登録数は上で MAX_VERIFY_HOLDERS 件までと確認済みのため、あふれない
+            let mut total: u64 = 0;
+            for (i, (who, qty)) in snapshot.iter().enumerate() {
+                ensure!(!snapshot[..i].iter().any(|(other, _)| other == who), "Duplicate account in snapshot");
+                total = total.checked_add(*qty).ok_or("Overflow adding the snapshot total")?;
+                if !<MyAssetsIndex<T>>::exists((who.clone(), asset_id)) {
+                    Self::my_asset_count(who).checked_add(1)
+                        .ok_or("Overflow adding a new My Asset to total supply")?;
+                }
+            }
+
+            // --------------------- 更新
+            for i in 0..Self::asset_holders_count(asset_id) {
+                let who = Self::asset_holder_by_index((asset_id, i));
+                if !snapshot.iter().any(|(other, _)| *other == who) {
+                    Self::_set_balance(&who, asset_id, 0)?;
+                }
+            }
+            for (who, qty) in snapshot.iter() {
+                Self::_set_balance(who, asset_id, *qty)?;
+            }
+            <TotalIssuedAssets<T>>::insert(asset_id, total);
+            Self::_record_supply(asset_id, total);
+            Self::_audit(asset_id);
+            // --------------------- 更新 --- ここまで
+
+            Ok(())
+        }
+
+        /// 資産名の規則の変更
+        /// sudo (root) のみ実行可能
+        fn set_name_policy(origin, policy: NamePolicy) -> Result {
+            ensure_root(origin)?;
+
+            <AssetNamePolicy<T>>::put(policy);
+
+            Ok(())
+        }
+
+        /// ブリッジ解放権限者の設定
+        /// sudo (root) のみ実行可能
+        fn set_bridge_authority(origin, authority: T::AccountId) -> Result {
+            ensure_root(origin)?;
+
+            <BridgeAuthority<T>>::put(authority);
+
+            Ok(())
+        }
+
+        /// 所有量の一括設定
+        /// sudo (root) のみ実行可能。テストネットで多数の保有者がいる状態を再現するためのもの
+        /// 発行済量は設定前後の所有量の差だけ増減する
+        ///
+        /// # Arguments
+        ///
+        /// `asset_id` - 資産 ID
+        /// `entries` - (アカウント, 所有量) の配列
+        fn sudo_set_balances(origin, asset_id: T::Hash, entries: Vec<(T::AccountId, u64)>) -> Result {
+            ensure_root(origin)?;
+
+            ensure!(<Assets<T>>::exists(asset_id), "This asset does not exist");
+            ensure!(!entries.is_empty(), "Empty batch");
+            ensure!(entries.len() <= MAX_BATCH_LEN, "Too many entries");
+            for (i, (who, _)) in entries.iter().enumerate() {
+                ensure!(!entries[..i].iter().any(|(prev, _)| prev == who), "Duplicate account in entries");
+            }
+
+            // 新しい発行済量と登録数を更新前に確認する
+            let mut total = Self::total_issued_asset(asset_id);
+            for (who, balance) in entries.iter() {
+                let key = (who.clone(), asset_id);
+                total = total.checked_sub(Self::my_asset_balance(&key))
+                    .ok_or("Underflow removing the old balance")?
+                    .checked_add(*balance)
+                    .ok_or("Overflow adding a new Asset")?;
+                if !<MyAssetsIndex<T>>::exists(&key) {
+                    Self::my_asset_count(who).checked_add(1)
+                        .ok_or("Overflow adding a new My Asset to total supply")?;
+                }
+            }
+
+            // --------------------- 更新
+            for (who, balance) in entries.iter() {
+                Self::_set_balance(who, asset_id, *balance)?;
+            }
+            <TotalIssuedAssets<T>>::insert(asset_id, total);
+            Self::_record_supply(asset_id, total);
+            Self::_audit(asset_id);
+            // --------------------- 更新 --- ここまで
+
+            Ok(())
+        }
+
+        /// 他チェーンへ送るための資産ロック
+        /// 呼び出し者の資産をブリッジアカウントへ移し、ロック情報を記録する
+        ///
+        /// # Arguments
+        ///
+        /// `asset_id` - 資産 ID
+        /// `qty` - ロック量
+        /// `target` - 送信先チェーンのアドレス
+        fn lock_for_bridge(origin, asset_id: T::Hash, qty: u64, target: Vec<u8>) -> Result {
+            let sender = ensure_signed(origin)?;
+
+            ensure!(!target.is_empty(), "Bridge target must not be empty");
+
+            // ロック ID 生成
+            let nonce = <Nonce<T>>::get();
+            let lock_id = (<system::Module<T>>::random_seed(), &sender, nonce)
+                .using_encoded(<T as system::Trait>::Hashing::hash);
+            ensure!(!<BridgeLocks<T>>::exists(lock_id), "Bridge lock already exists");
+            Self::_ensure_sender_allowed(&sender, asset_id)?;
+
+            Self::_send_asset(sender.clone(), Self::bridge_account(), asset_id, qty)?;
+
+            // --------------------- 更新
+            Self::_record_send(&sender, asset_id);
+            <BridgeLocks<T>>::insert(lock_id, BridgeLock {
+                who: sender.clone(),
+                asset_id,
+                qty,
+                target,
+            });
+            <Nonce<T>>::mutate(|n| *n += 1);
+            // --------------------- 更新 --- ここまで
+
+            Self::_deposit_event(RawEvent::BridgeLocked(sender, lock_id, asset_id, qty));
+
+            Ok(())
+        }
+
+        /// 他チェーンから戻ってきた資産の解放
+        /// ブリッジ解放権限者のみ実行可能
+        /// 解放量はロックの残量から差し引き、残量が 0 になったロックは削除する
+        ///
+        /// # Arguments
+        ///
+        /// `lock_id` - 解放元のロック ID
+        /// `to` - 受信者アドレス
+        /// `qty` - 解放量 (ロックの残量まで)
+        fn release_from_bridge(origin, lock_id: T::Hash, to: T::AccountId, qty: u64) -> Result {
+            let sender = ensure_signed(origin)?;
+
+            let authority = Self::bridge_authority().ok_or("No bridge authority")?;
+            ensure!(sender == authority, "You are not the bridge authority");
+            let mut lock = Self::bridge_lock(lock_id).ok_or("This bridge lock does not exist")?;
+            lock.qty = lock.qty.checked_sub(qty).ok_or("Release exceeds the bridge lock")?;
+            let asset_id = lock.asset_id;
+            Self::_ensure_recipient_allowed(&to, asset_id)?;
+
+            Self::_send_asset(Self::bridge_account(), to.clone(), asset_id, qty)?;
+
+            // --------------------- 更新
+            if lock.qty == 0 {
+                <BridgeLocks<T>>::remove(lock_id);
+            } else {
+                <BridgeLocks<T>>::insert(lock_id, lock);
+            }
+            // --------------------- 更新 --- ここまで
+
+            Self::_deposit_event(RawEvent::BridgeReleased(to, asset_id, qty));
+
+            Ok(())
+        }
+
+        /// 署名付き交換
+        /// 署名者が `give_qty` の `give_asset` を `counterparty` へ送り、同時に `counterparty` から
+        /// `want_qty` の `want_asset` を受け取る。どちらかが送れない場合はどちらも送らない
+        /// `counterparty` は事前に swap_payload の内容に署名しておく
+        /// `nonce` は swap_nonce((counterparty, 署名者)) と一致する必要があり、成功すると 1 増える
+        /// 両方の送信で sendasset と同じく、コミュニティ基金への分配と焼却を差し引く
+        fn atomic_swap_signed(
+            origin,
+            counterparty: T::AccountId,
+            counterparty_sig: T::Signature,
+            give_asset: T::Hash,
+            give_qty: u64,
+            want_asset: T::Hash,
+            want_qty: u64,
+            nonce: u64
+        ) -> Result {
+            let sender = ensure_signed(origin)?;
+
+            ensure!(counterparty != sender, "You can not swap with yourself");
+            ensure!(give_asset != want_asset, "You can not swap an asset for itself");
+            let key = (counterparty.clone(), sender.clone());
+            ensure!(nonce == Self::swap_nonce(&key), "Invalid swap nonce");
+            let payload = Self::swap_payload(&sender, &counterparty, give_asset, give_qty, want_asset, want_qty, nonce);
+            ensure!(counterparty_sig.verify(&payload[..], &counterparty), "Invalid counterparty signature");
+
+            // 両方の送信を更新前に確認する
+            // 資産が異なるため、一方の送信で他方の確認結果は変わらない
+            for &(from, to, asset_id, qty) in [(&sender, &counterparty, give_asset, give_qty), (&counterparty, &sender, want_asset, want_qty)].iter() {
+                ensure!(<Assets<T>>::exists(asset_id), "This asset does not exist");
+                Self::_ensure_transfer_allowed(from, to, asset_id, false)?;
+                Self::_ensure_can_send_with_deductions(from, to, asset_id, qty)?;
+            }
+
+            // --------------------- 更新
+            let (give_net, give_burn) = Self::_send_with_deductions(&sender, &counterparty, give_asset, give_qty)?;
+            let (want_net, want_burn) = Self::_send_with_deductions(&counterparty, &sender, want_asset, want_qty)?;
+            Self::_record_send(&sender, give_asset);
+            Self::_record_send(&counterparty, want_asset);
+            <SwapNonce<T>>::insert(&key, nonce + 1);
+            // --------------------- 更新 --- ここまで
+
+            Self::_deposit_sent_with_burn(sender.clone(), counterparty.clone(), give_asset, give_net, give_burn);
+            Self::_deposit_sent_with_burn(counterparty.clone(), sender.clone(), want_asset, want_net, want_burn);
+            Self::_deposit_event(RawEvent::SwappedSigned(sender, counterparty, nonce));
+
+            Ok(())
+        }
+
+        /// 受信者の承諾が必要な送信
+        /// 送信量をエスクローアカウントへ移し、受信者が accept_transfer すると届く
+        /// コミュニティ基金への分配と焼却は預けるときに差し引き、取り消しや期限切れでも戻らない
+        /// 承諾前であれば送信者は cancel_transfer で取り消せる
+        fn propose_transfer(origin, to: T::AccountId, asset_id: T::Hash, qty: u64) -> Result {
+            let sender = ensure_signed(origin)?;
+
+            Self::_propose_transfer(sender, to, asset_id, qty)?;
+
+            Ok(())
+        }
+
+        /// 期限付き送信
+        /// propose_transfer と同じくエスクローアカウントへ預け、受信者が acknowledge_receipt すると届く
+        /// `expires_in` ブロック後のブロックの終わりまでに受信確認されなければ、送信者へ自動で戻す
+        /// 期限前であれば送信者は cancel_transfer で取り消せる
+        fn propose_expiring_transfer(origin, to: T::AccountId, asset_id: T::Hash, qty: u64, expires_in: T::BlockNumber) -> Result {
+            let sender = ensure_signed(origin)?;
+
+            ensure!(!expires_in.is_zero(), "Expiry must be in the future");
+            let expiry = <system::Module<T>>::block_number().checked_add(&expires_in)
+                .ok_or("Overflow computing the expiry")?;
+            ensure!(Self::expiring_transfers(expiry).len() < MAX_EXPIRING_PER_BLOCK, "Too many transfers expiring in that block");
+
+            let transfer_id = Self::_propose_transfer(sender, to, asset_id, qty)?;
+
+            // --------------------- 更新
+            <TransferExpiry<T>>::insert(transfer_id, expiry);
+            <ExpiringTransfers<T>>::mutate(expiry, |ids| ids.push(transfer_id));
+            // --------------------- 更新 --- ここまで
+
+            Ok(())
+        }
+
+        /// 承諾待ち送信の承諾
+        /// 受信者のみ実行可能
+        fn accept_transfer(origin, transfer_id: T::Hash) -> Result {
+            let sender = ensure_signed(origin)?;
+
+            Self::_accept_transfer(sender, transfer_id)
+        }
+
+        /// 期限付き送信の受信確認
+        /// 受信者のみ実行可能。期限までに確認すると、その時点で受信者へ届く
+        fn acknowledge_receipt(origin, transfer_id: T::Hash) -> Result {
+            let sender = ensure_signed(origin)?;
+
+            ensure!(<TransferExpiry<T>>::exists(transfer_id), "This transfer does not expire");
+
+            Self::_accept_transfer(sender, transfer_id)
+        }
+
+        /// 承諾待ち送信の取り消し
+        /// 送信者のみ実行可能。預けた量は送信者へ戻る
+        fn cancel_transfer(origin, transfer_id: T::Hash) -> Result {
+            let sender = ensure_signed(origin)?;
+
+            let transfer = Self::pending_transfer(transfer_id).ok_or("This pending transfer does not exist")?;
+            ensure!(transfer.from == sender, "You are not the sender of this transfer");
+
+            // --------------------- 更新
+            Self::_send_asset(Self::escrow_account(), sender, transfer.asset_id, transfer.qty)?;
+            Self::_remove_pending_transfer(transfer_id, &transfer);
+            // --------------------- 更新 --- ここまで
+
+            Self::_deposit_event(RawEvent::TransferCancelled(transfer_id));
+
+            Ok(())
+        }
+
+        /// エアドロップ作成
+        /// 発行者のみ実行可能
+        /// 配布総量 `total` をエアドロップアカウントへ移し、受取人はマークル証明で受け取る
+        fn create_airdrop(origin, asset_id: T::Hash, root: T::Hash, total: u64) -> Result {
+            let sender = ensure_signed(origin)?;
+
+            let owner = Self::owner_of(asset_id).ok_or("No owner for this asset")?;
+            ensure!(owner == sender, "You do not own this asset");
+
+            // エアドロップ ID 生成
+            let nonce = <Nonce<T>>::get();
+            let airdrop_id = (<system::Module<T>>::random_seed(), &sender, nonce)
+                .using_encoded(<T as system::Trait>::Hashing::hash);
+            ensure!(!<MerkleAirdrops<T>>::exists(airdrop_id), "Airdrop already exists");
+            Self::_ensure_sender_allowed(&sender, asset_id)?;
+
+            Self::_send_asset(sender.clone(), Self::airdrop_account(), asset_id, total)?;
+
+            // --------------------- 更新
+            Self::_record_send(&sender, asset_id);
+            <MerkleAirdrops<T>>::insert(airdrop_id, (root, asset_id));
+            <AirdropRemaining<T>>::insert(airdrop_id, total);
+            <Nonce<T>>::mutate(|n| *n += 1);
+            // --------------------- 更新 --- ここまで
+
+            Self::_deposit_event(RawEvent::AirdropCreated(sender, airdrop_id, asset_id, total));
+
+            Ok(())
+        }
+
+        /// エアドロップ受け取り
+        /// (呼び出し者, `amount`) の葉が `proof` でルートにつながれば受け取れる (1 アカウント 1 回)
+        fn claim_airdrop(origin, airdrop_id: T::Hash, amount: u64, proof: Vec<T::Hash>) -> Result {
+            let sender = ensure_signed(origin)?;
+
+            let (root, asset_id) = Self::merkle_airdrop(airdrop_id).ok_or("This airdrop does not exist")?;
+            ensure!(!Self::airdrop_claimed((airdrop_id, sender.clone())), "Airdrop already claimed");
+
+            let leaf = <T as system::Trait>::Hashing::hash_of(&(sender.clone(), amount));
+            ensure!(Self::_merkle_root(leaf, &proof) == root, "Invalid airdrop proof");
+
+            let remaining = Self::airdrop_remaining(airdrop_id).checked_sub(amount)
+                .ok_or("Airdrop has not enough left")?;
+            Self::_ensure_recipient_allowed(&sender, asset_id)?;
+
+            // --------------------- 更新
+            Self::_send_asset(Self::airdrop_account(), sender.clone(), asset_id, amount)?;
+            <AirdropRemaining<T>>::insert(airdrop_id, remaining);
+            <AirdropClaims<T>>::insert((airdrop_id, sender.clone()), true);
+            // --------------------- 更新 --- ここまで
+
+            Self::_deposit_event(RawEvent::AirdropClaimed(sender, airdrop_id, amount));
+
+            Ok(())
+        }
+
+        /// フォーセットの設定
+        /// 発行者のみ実行可能
+        /// `amount` が 0 より大きければ、faucet 1 回で `amount` を呼び出し者へ追加発行する (0 で無効)
+        fn set_faucet(origin, asset_id: T::Hash, amount: u64) -> Result {
+            let sender = ensure_signed(origin)?;
+
+            let owner = Self::owner_of(asset_id).ok_or("No owner for this asset")?;
+            ensure!(owner == sender, "You do not own this asset");
+            if amount > 0 {
+                ensure!(Self::asset(asset_id).open, "You can not issue more");
+                let max_mint = Self::max_mint_per_call(asset_id);
+                ensure!(max_mint == 0 || amount <= max_mint, "Mint exceeds per-call cap");
+            }
+
+            <FaucetAmount<T>>::insert(asset_id, amount);
+            Self::_log_owner_action(asset_id, b"set_faucet");
+
+            Ok(())
+        }
+
+        /// 猶予付きの所有者変更の猶予の設定
+        /// sudo (root) のみ実行可能。開始済みの変更の確定ブロックは変わらない
+        fn set_owner_rotation_delay(origin, blocks: T::BlockNumber) -> Result {
+            ensure_root(origin)?;
+            ensure!(!blocks.is_zero(), "Owner rotation delay must not be zero");
+
+            <OwnerRotationDelay<T>>::put(blocks);
+
+            Ok(())
+        }
+
+        /// フォーセットの受け取り間隔の設定
+        /// sudo (root) のみ実行可能
+        fn set_faucet_cooldown(origin, blocks: T::BlockNumber) -> Result {
+            ensure_root(origin)?;
+
+            <FaucetCooldown<T>>::put(blocks);
+
+            Ok(())
+        }
+
+        /// フォーセット
+        /// フォーセットが有効な資産を、設定された量だけ呼び出し者へ追加発行する
+        /// 同じアカウントは FaucetCooldown ブロック空けないと再び受け取れない
+        /// 受信者側の送信ポリシー・保有者数の上限・1 回の追加発行量の上限は、送信で受け取る場合と同じく確認する
+        fn faucet(origin, asset_id: T::Hash) -> Result {
+            let sender = ensure_signed(origin)?;
+
+            let asset = Self::try_asset(asset_id).ok_or("This asset does not exist")?;
+            let amount = Self::faucet_amount(asset_id);
+            ensure!(amount > 0, "Faucet is not enabled for this asset");
+            ensure!(asset.open, "You can not issue more");
+            // set_faucet の後に上限が下げられている場合がある
+            let max_mint = Self::max_mint_per_call(asset_id);
+            ensure!(max_mint == 0 || amount <= max_mint, "Mint exceeds per-call cap");
+            Self::_ensure_recipient_allowed(&sender, asset_id)?;
+            let max_holders = Self::max_holders(asset_id);
+            if max_holders > 0 && Self::my_asset_balance((sender.clone(), asset_id)) == 0 {
+                ensure!(Self::holder_count(asset_id) < max_holders, "Max holders reached");
+            }
+
+            let now = <system::Module<T>>::block_number();
+            if let Some(last) = Self::last_faucet(&sender) {
+                ensure!(now - last >= Self::faucet_cooldown(), "Faucet cooldown has not passed");
+            }
+
+            let new_total_issued_asset = Self::total_issued_asset(asset_id).checked_add(amount)
+                .ok_or("Overflow adding a new Asset")?;
+            let new_my_asset_balance = Self::my_asset_balance((sender.clone(), asset_id)).checked_add(amount)
+                .ok_or("Overflow adding a new Asset to the owner's balance")?;
+
+            // --------------------- 更新
+            Self::_set_balance(&sender, asset_id, new_my_asset_balance)?;
+            <TotalIssuedAssets<T>>::insert(asset_id, new_total_issued_asset);
+            Self::_record_supply(asset_id, new_total_issued_asset);
+            Self::_audit(asset_id);
+            <LastFaucet<T>>::insert(&sender, now);
+            // --------------------- 更新 --- ここまで
+
+            // 発行済量を追うインデクサのため、追加発行と同じイベントも発行する
+            let nonce = Self::_next_account_nonce(&sender);
+            Self::_deposit_event(RawEvent::IssuedMore(sender.clone(), asset_id, amount, Self::_next_event_seq(), nonce));
+            Self::_deposit_event(RawEvent::FaucetClaimed(sender, asset_id, amount));
+
+            Ok(())
+        }
+
+        /// ラップ
+        /// `base_asset` の `qty` から、sendasset と同じくコミュニティ基金への分配と焼却を差し引いた残りを
+        /// ラップアカウントにロックし、ロックした量と同量の新しいラップ資産を発行する
+        /// (ラップ資産の送信には元資産の控除がかからないため、ロックするときに差し引く)
+        /// ラップ資産は追加発行できない
+        fn wrap(origin, base_asset: T::Hash, qty: u64, wrapped_name: Vec<u8>) -> Result {
+            let sender = ensure_signed(origin)?;
+
+            // 元資産のロックが失敗しないことを先に確認する
+            // (_issue は失敗する場合は更新前に失敗するため、ロックを後に行う)
+            ensure!(!<WrappedBacking<T>>::exists(base_asset), "Wrapped assets can not be wrapped again");
+            Self::_ensure_sender_allowed(&sender, base_asset)?;
+            let (split, burn) = Self::_ensure_can_send_with_deductions(&sender, &Self::wrap_account(), base_asset, qty)?;
+            let locked = qty - split.as_ref().map_or(0, |(_, cut)| *cut) - burn;
+
+            // --------------------- 更新
+            let wrapped_asset = Self::_issue(sender.clone(), wrapped_name, locked, false)?;
+            Self::_send_with_deductions(&sender, &Self::wrap_account(), base_asset, qty)?;
+            Self::_record_send(&sender, base_asset);
+            <WrappedBacking<T>>::insert(wrapped_asset, (base_asset, locked));
+            // --------------------- 更新 --- ここまで
+
+            if burn > 0 {
+                let nonce = Self::_next_account_nonce(&sender);
+                Self::_deposit_event(RawEvent::Burned(sender.clone(), base_asset, burn, Self::_next_event_seq(), nonce));
+            }
+            Self::_deposit_event(RawEvent::Wrapped(sender, base_asset, wrapped_asset, locked));
+
+            Ok(())
+        }
+
+        /// アンラップ
+        /// ラップ資産を `qty` だけ焼却し、同量の元資産を受け取る
+        fn unwrap(origin, wrapped_asset: T::Hash, qty: u64) -> Result {
+            let sender = ensure_signed(origin)?;
+
+            let (base_asset, backing) = Self::wrapped_backing(wrapped_asset)
+                .ok_or("This asset is not a wrapped asset")?;
+            ensure!(qty <= backing, "Unwrap exceeds backing");
+            ensure!(Self::my_asset_balance((Self::wrap_account(), base_asset)) >= qty,
+                "Wrap account holds less than the backing");
+            Self::_ensure_recipient_allowed(&sender, base_asset)?;
+            // 元資産の返却が失敗しないことを先に確認する
+            // (_burn は失敗する場合は更新前に失敗するため、返却を後に行う)
+            Self::_ensure_can_send(&Self::wrap_account(), &sender, base_asset, qty)?;
+
+            // --------------------- 更新
+            Self::_burn(sender.clone(), wrapped_asset, qty)?;
+            Self::_send_asset(Self::wrap_account(), sender.clone(), base_asset, qty)?;
+            <WrappedBacking<T>>::insert(wrapped_asset, (base_asset, backing - qty));
+            // --------------------- 更新 --- ここまで
+
+            Self::_deposit_event(RawEvent::Unwrapped(sender, wrapped_asset, qty));
+
+            Ok(())
+        }
+    }
+}
+
+impl<T: Trait> Module<T> {
+    /// モジュール管理アカウント
+    /// タグのハッシュから導出するため、秘密鍵は存在しない
+    fn module_account(tag: &[u8]) -> T::AccountId {
+        let hash = <T as system::Trait>::Hashing::hash(tag);
+        let mut input: &[u8] = hash.as_ref();
+        T::AccountId::decode(&mut input).unwrap_or_default()
+    }
+
+    /// ブリッジアカウント
+    /// ロック中の資産を保持する
+    pub fn bridge_account() -> T::AccountId {
+        Self::module_account(b"ibchain/bridge")
+    }
+
+    /// ラップアカウント
+    /// ラップ資産の裏付けとなる元資産を保持する
+    pub fn wrap_account() -> T::AccountId {
+        Self::module_account(b"ibchain/wrap")
+    }
+
+    /// エアドロップアカウント
+    /// 受け取り前のエアドロップ資産を保持する
+    pub fn airdrop_account() -> T::AccountId {
+        Self::module_account(b"ibchain/airdrop")
+    }
+
+    /// エスクローアカウント
+    /// 承諾待ち送信の資産を保持する
+    pub fn escrow_account() -> T::AccountId {
+        Self::module_account(b"ibchain/escrow")
+    }
+
+    /// モジュール管理アカウントか
+    /// これらのアカウントの残高はロック情報や裏付けと一致している必要がある
+    pub fn is_reserved_account(who: &T::AccountId) -> bool {
+        *who == Self::bridge_account()
+            || *who == Self::wrap_account()
+            || *who == Self::airdrop_account()
+            || *who == Self::escrow_account()
+    }
+
+    /// マークル証明からルートを計算する
+    /// 各段で小さい方を先にして連結するため、証明に左右の情報は不要
+    fn _merkle_root(leaf: T::Hash, proof: &[T::Hash]) -> T::Hash {
+        proof.iter().fold(leaf, |node, sibling| {
+            if node.as_ref() <= sibling.as_ref() {
+                <T as system::Trait>::Hashing::hash_of(&(node, *sibling))
+            } else {
+                <T as system::Trait>::Hashing::hash_of(&(*sibling, node))
+            }
+        })
+    }
+
+    /// オリジナル資産
+    /// 存在しない場合、asset は既定値 (空の名前・既定のハッシュ・open == false) を返すがこちらは None を返す
+    pub fn try_asset(asset_id: T::Hash) -> Option<Asset<T::Hash>> {
+        if <Assets<T>>::exists(asset_id) {
+            Some(Self::asset(asset_id))
+        } else {
+            None
+        }
+    }
+
+    /// 全資産配列の `index` 番目
+    /// 範囲外の場合、asset_by_index は既定値を返すがこちらは None を返す
+    pub fn try_asset_by_index(index: u64) -> Option<T::Hash> {
+        if index < Self::all_asset_count() {
+            Some(Self::asset_by_index(index))
+        } else {
+            None
+        }
+    }
+
+    /// 全資産配列の `start` 番目から最大 `limit` 件 (limit は MAX_LIST_LEN まで) のうち、
+    /// `open` (追加発行できるか) が一致する資産 (資産ID, 資産名)
+    /// 読む件数を制限するため、結果は limit 件より少ないことがある。続きは start + limit から取得する
+    pub fn assets_by_open(open: bool, start: u64, limit: u64) -> Vec<(T::Hash, Vec<u8>)> {
+        let end = start.saturating_add(rstd::cmp::min(limit, MAX_LIST_LEN));
+        (start..rstd::cmp::min(end, Self::all_asset_count()))
+            .filter_map(Self::try_asset_by_index)
+            .filter_map(Self::try_asset)
+            .filter(|asset| asset.open == open)
+            .map(|asset| (asset.id, asset.name))
+            .collect()
+    }
+
+    /// 発行した資産配列の `index` 番目。範囲外の場合は None
+    pub fn try_asset_of_owner_by_index(who: &T::AccountId, index: u64) -> Option<T::Hash> {
+        if index < Self::owned_asset_count(who) {
+            Some(Self::asset_of_owner_by_index((who.clone(), index)))
+        } else {
+            None
+        }
+    }
+
+    /// 所有している資産配列の `index` 番目。範囲外の場合は None
+    pub fn try_my_asset_by_index(who: &T::AccountId, index: u64) -> Option<T::Hash> {
+        if index < Self::my_asset_count(who) {
+            Some(Self::my_asset_by_index((who.clone(), index)))
+        } else {
+            None
+        }
+    }
+
+    /// 所有している資産の一覧 (資産ID, 所有量)
+    /// 先頭から MAX_LIST_LEN 件まで。それ以降は my_assets_page で取得する
+    pub fn my_assets(who: &T::AccountId) -> Vec<(T::Hash, u64)> {
+        Self::my_assets_page(who, 0, MAX_LIST_LEN)
+    }
+
+    /// 所有している資産の一覧のうち `start` 番目から最大 `limit` 件 (limit は MAX_LIST_LEN まで)
+    pub fn my_assets_page(who: &T::AccountId, start: u64, limit: u64) -> Vec<(T::Hash, u64)> {
+        let end = start.saturating_add(rstd::cmp::min(limit, MAX_LIST_LEN));
+        (start..rstd::cmp::min(end, Self::my_asset_count(who)))
+            .filter_map(|i| Self::try_my_asset_by_index(who, i))
+            .map(|asset_id| (asset_id, Self::my_asset_balance((who.clone(), asset_id))))
+            .collect()
+    }
+
+    /// 所有している資産の一覧 (資産ID, 資産名, 所有量)
+    /// my_assets のうち所有量が 0 でないもの
+    pub fn portfolio(who: &T::AccountId) -> Vec<(T::Hash, Vec<u8>, u64)> {
+        Self::my_assets(who)
+            .into_iter()
+            .filter(|&(_, balance)| balance > 0)
+            .map(|(asset_id, balance)| (asset_id, Self::asset(asset_id).name, balance))
+            .collect()
+    }
+
+    /// 2 アカウントの所有資産の比較 (資産ID, a の所有量, b の所有量)
+    /// どちらかが所有している資産を a の資産配列順、続いて b のみが所有する資産の順に返す
+    /// 各アカウントの先頭 MAX_LIST_LEN 件までを見て、結果も MAX_LIST_LEN 件まで
+    pub fn holdings_diff(a: &T::AccountId, b: &T::AccountId) -> Vec<(T::Hash, u64, u64)> {
+        let mut diff: Vec<(T::Hash, u64, u64)> = Self::my_assets(a)
+            .into_iter()
+            .map(|(asset_id, a_balance)| (asset_id, a_balance, Self::my_asset_balance((b.clone(), asset_id))))
+            .collect();
+        for (asset_id, b_balance) in Self::my_assets(b) {
+            if diff.len() as u64 >= MAX_LIST_LEN {
+                break;
+            }
+            if !diff.iter().any(|&(id, _, _)| id == asset_id) {
+                diff.push((asset_id, 0, b_balance));
+            }
+        }
+        diff
+    }
+
+    /// 発行した資産の一覧
+    /// 先頭から MAX_LIST_LEN 件まで。それ以降は owned_assets_page で取得する
+    pub fn owned_assets(who: &T::AccountId) -> Vec<T::Hash> {
+        Self::owned_assets_page(who, 0, MAX_LIST_LEN)
+    }
+
+    /// 発行した資産の一覧のうち `start` 番目から最大 `limit` 件 (limit は MAX_LIST_LEN まで)
+    pub fn owned_assets_page(who: &T::AccountId, start: u64, limit: u64) -> Vec<T::Hash> {
+        let end = start.saturating_add(rstd::cmp::min(limit, MAX_LIST_LEN));
+        (start..rstd::cmp::min(end, Self::owned_asset_count(who)))
+            .filter_map(|i| Self::try_asset_of_owner_by_index(who, i))
+            .collect()
+    }
+
+    /// アカウントの概要
+    pub fn account_overview(who: &T::AccountId) -> AccountOverview<T::Hash> {
+        AccountOverview {
+            held: Self::my_assets(who),
+            held_truncated: Self::my_asset_count(who) > MAX_LIST_LEN,
+            issued: Self::owned_assets(who),
+            issued_truncated: Self::owned_asset_count(who) > MAX_LIST_LEN,
+        }
+    }
+
+    /// 投票ロック中の量
+    /// 期限を過ぎたロックは解除前でも 0 として扱う
+    pub fn locked_balance(who: &T::AccountId, asset_id: T::Hash) -> u64 {
+        let key = (who.clone(), asset_id);
+        if !<VoteLocks<T>>::exists(&key) {
+            return 0;
+        }
+        let (locked, until) = Self::vote_lock(&key);
+        if <system::Module<T>>::block_number() < until {
+            locked
+        } else {
+            0
+        }
+    }
+
+    /// 送信・焼却できる量
+    pub fn spendable_balance(who: &T::AccountId, asset_id: T::Hash) -> u64 {
+        Self::my_asset_balance((who.clone(), asset_id))
+            .saturating_sub(Self::locked_balance(who, asset_id))
+    }
+
+    /// (所有量, そのうち送信・焼却できる量)
+    /// 現在のブロックで有効なロックをすべて差し引く
+    pub fn total_and_spendable(who: &T::AccountId, asset_id: T::Hash) -> (u64, u64) {
+        (Self::my_asset_balance((who.clone(), asset_id)), Self::spendable_balance(who, asset_id))
+    }
+
+    /// 所有量の多い保有者 (アカウント, 所有量) を多い順に最大 `n` 件
+    /// `n` は MAX_TOP_HOLDERS まで。保有者配列を全件読むため、費用は登録数に比例する
+    pub fn top_holders(asset_id: T::Hash, n: u32) -> Vec<(T::AccountId, u64)> {
+        let n = rstd::cmp::min(n, MAX_TOP_HOLDERS) as usize;
+        let mut holders: Vec<(T::AccountId, u64)> = (0..Self::asset_holders_count(asset_id))
+            .map(|i| {
+                let who = Self::asset_holder_by_index((asset_id, i));
+                let balance = Self::my_asset_balance((who.clone(), asset_id));
+                (who, balance)
+            })
+            .filter(|(_, balance)| *balance > 0)
+            .collect();
+        // 同量の場合は登録順
+        holders.sort_by(|a, b| b.1.cmp(&a.1));
+        holders.truncate(n);
+        holders
+    }
+
+    /// 保有者ごとの所有量 (アカウント, 所有量) を保有者配列の順に返す。所有量 0 の登録は除く
+    /// import_snapshot にそのまま渡せる。保有者配列の先頭 MAX_VERIFY_HOLDERS 件までを読む
+    pub fn snapshot(asset_id: T::Hash) -> Vec<(T::AccountId, u64)> {
+        (0..rstd::cmp::min(Self::asset_holders_count(asset_id), MAX_VERIFY_HOLDERS))
+            .map(|i| {
+                let who = Self::asset_holder_by_index((asset_id, i));
+                let balance = Self::my_asset_balance((who.clone(), asset_id));
+                (who, balance)
+            })
+            .filter(|(_, balance)| *balance > 0)
+            .collect()
+    }
+
+    /// 所有量 (ERC-20 balanceOf)
+    pub fn balance_of(asset_id: T::Hash, who: &T::AccountId) -> u64 {
+        Self::my_asset_balance((who.clone(), asset_id))
+    }
+
+    /// 発行済量 (ERC-20 totalSupply)
+    pub fn total_supply(asset_id: T::Hash) -> u64 {
+        Self::total_issued_asset(asset_id)
+    }
+
+    /// 発行済量
+    /// 存在しない資産では、total_issued_asset は 0 を返すがこちらは None を返す
+    /// 全量を焼却した資産は Some(0) になる
+    pub fn try_total_issued(asset_id: T::Hash) -> Option<u64> {
+        if <Assets<T>>::exists(asset_id) {
+            Some(Self::total_issued_asset(asset_id))
+        } else {
+            None
+        }
+    }
+
+    /// 所有量のスナップショット (ガバナンス用)
+    /// ランタイム API は指定したブロックの状態で実行されるため、提案作成時のブロックハッシュを
+    /// 指定して呼べばその時点の所有量になる (ノードがそのブロックの状態を保持している場合)
+    pub fn balance_at(who: &T::AccountId, asset_id: T::Hash) -> u64 {
+        Self::balance_of(asset_id, who)
+    }
+
+    /// 発行済量のスナップショット (ガバナンス用)
+    /// balance_at と同じく、呼び出し時に指定したブロックの状態を読む
+    pub fn total_supply_at(asset_id: T::Hash) -> u64 {
+        Self::total_supply(asset_id)
+    }
+
+    /// 資産の統計
+    pub fn asset_stats(asset_id: T::Hash) -> AssetStats {
+        AssetStats {
+            total_issued: Self::total_issued_asset(asset_id),
+            holder_count: Self::holder_count(asset_id),
+            total_volume: Self::total_volume(asset_id),
+        }
+    }
+
+    /// 資産の設定
+    /// 存在しない資産は None
+    pub fn asset_config(asset_id: T::Hash) -> Option<AssetConfig<T::AccountId, T::BlockNumber>> {
+        let asset = Self::try_asset(asset_id)?;
+        Some(AssetConfig {
+            open: asset.open,
+            burnable: Self::is_burnable(asset_id),
+            frozen: Self::frozen(asset_id),
+            require_memo: Self::require_memo(asset_id),
+            requires_allowlist: Self::requires_allowlist(asset_id),
+            restrict_destinations: Self::restrict_destinations(asset_id),
+            events_enabled: Self::events_enabled(asset_id),
+            community_fund: Self::community_fund(asset_id),
+            community_bps: Self::community_bps(asset_id),
+            burn_bps: Self::burn_bps(asset_id),
+            max_holders: Self::max_holders(asset_id),
+            max_mint_per_call: Self::max_mint_per_call(asset_id),
+            cooldown: Self::cooldown(asset_id),
+        })
+    }
+
+    /// 資産が使っているストレージの見積もり (バイト)
+    /// 資産情報と資産ごとのエントリに、保有者配列の登録数 × 保有者ごとのエントリを足す
+    /// 件数から計算するため保有者配列は読まない。使用許可量・投票ロックなどの任意のエントリは含まない
+    /// 存在しない資産は 0
+    pub fn storage_footprint(asset_id: T::Hash) -> u64 {
+        let asset = match Self::try_asset(asset_id) {
+            Some(asset) => asset,
+            None => return 0,
+        };
+        let account_len = T::AccountId::default().encode().len() as u64;
+        let hash_len = T::Hash::default().encode().len() as u64;
+        let count_len = 0u64.encode().len() as u64;
+
+        // Assets, AssetOwner (Option), AllAssetsArray, AllAssetsIndex, OwnedAssetsArray, OwnedAssetsIndex,
+        // TotalIssuedAssets, HolderCount, AssetHoldersCount
+        let fixed = asset.encode().len() as u64
+            + (1 + account_len)
+            + hash_len + count_len
+            + hash_len + count_len
+            + 3 * count_len
+            + 9 * STORAGE_KEY_LEN;
+        // MyAssetsArray, MyAssetsIndex, MyAssetBalances, AssetHoldersArray, AssetHoldersIndex
+        let per_holder = hash_len + count_len + count_len + account_len + count_len + 5 * STORAGE_KEY_LEN;
+
+        fixed.saturating_add(Self::asset_holders_count(asset_id).saturating_mul(per_holder))
+    }
+
+    /// 資産の整合性確認
+    /// 保有者配列の所有量の合計が発行済量 (発行 + 追加発行 - 焼却) と一致し、
+    /// 所有量が 0 でない保有者の数が HolderCount と一致すれば true
+    /// 保有者配列を全件読むため、費用は登録数に比例する
+    /// 登録数が MAX_VERIFY_HOLDERS を超える資産は確認せず false を返す
+    pub fn verify_asset(asset_id: T::Hash) -> bool {
+        let holders_count = Self::asset_holders_count(asset_id);
+        if holders_count > MAX_VERIFY_HOLDERS {
+            return false;
+        }
+
+        let mut total: u64 = 0;
+        let mut holding: u64 = 0;
+        for i in 0..holders_count {
+            let who = Self::asset_holder_by_index((asset_id, i));
+            let balance = Self::my_asset_balance((who, asset_id));
+            total = match total.checked_add(balance) {
+                Some(total) => total,
+                None => return false,
+            };
+            if balance > 0 {
+                holding += 1;
+            }
+        }
+
+        total == Self::total_issued_asset(asset_id) && holding == Self::holder_count(asset_id)
+    }
+
+    /// 発行時の制約
+    pub fn issuance_policy() -> IssuancePolicy {
+        IssuancePolicy {
+            min_issue_qty: Self::min_issue_qty(),
+            name_policy: Self::name_policy(),
+            max_collection_items: MAX_BATCH_LEN as u32,
+        }
+    }
+
+    /// 追加発行できる資産の一覧
+    /// 発行者であり、かつ追加発行可能 (open) な資産を返す
+    pub fn mintable_assets(who: &T::AccountId) -> Vec<T::Hash> {
+        Self::owned_assets(who)
+            .into_iter()
+            .filter(|asset_id| Self::asset(asset_id).open)
+            .collect()
+    }
+
+    /// `who` が `asset_id` をあとどれだけ追加発行できるか
+    /// 発行者でない場合と追加発行できない (open でない) 資産では None
+    pub fn mint_capacity(who: &T::AccountId, asset_id: T::Hash) -> Option<u64> {
+        if Self::owner_of(asset_id).as_ref() != Some(who) || !Self::asset(asset_id).open {
+            return None;
+        }
+        Some(Self::remaining_mintable(asset_id))
+    }
+
+    /// 資産をあとどれだけ追加発行できるか (発行者を問わない)
+    /// 発行上限はないため、発行済量が u64 であふれない範囲がそのまま残りになる
+    /// 発行済量が u64 の最大値に達した資産は、open でもそれ以上追加発行できない
+    /// 追加発行できない (open でない) 資産と存在しない資産は 0
+    pub fn remaining_mintable(asset_id: T::Hash) -> u64 {
+        match Self::try_asset(asset_id) {
+            Some(ref asset) if asset.open => u64::max_value() - Self::total_issued_asset(asset_id),
+            _ => 0,
+        }
+    }
+
+    /// イベントの出力
+    /// deposit_event は直接呼ばず、必ずこの関数を通す
+    /// EventVerbosity がイベントのレベルより低ければ出力しない
+    fn _deposit_event(event: Event<T>) {
+        if Self::_event_level(&event) <= Self::event_verbosity() {
+            Self::deposit_event(event);
+        }
+    }
+
+    /// イベントのレベル
+    /// EVENTS_CORE: 所有量 (エスクロー・ブリッジなどのモジュール管理アカウントを含む) と所有者の変化
+    fn _event_level(event: &Event<T>) -> u8 {
+        match event {
+            RawEvent::Issued(..)
+            | RawEvent::IssuedMore(..)
+            | RawEvent::SentAsset(..)
+            | RawEvent::BatchSent(..)
+            | RawEvent::Burned(..)
+            | RawEvent::BridgeLocked(..)
+            | RawEvent::BridgeReleased(..)
+            | RawEvent::Wrapped(..)
+            | RawEvent::Unwrapped(..)
+            | RawEvent::AirdropCreated(..)
+            | RawEvent::AirdropClaimed(..)
+            | RawEvent::FaucetClaimed(..)
+            | RawEvent::OwnershipTransferred(..)
+            | RawEvent::TransferProposed(..)
+            | RawEvent::TransferAccepted(..)
+            | RawEvent::TransferCancelled(..)
+            | RawEvent::TransferExpired(..)
+            | RawEvent::CommunityFunded(..) => EVENTS_CORE,
+            RawEvent::SentWithMemo(..)
+            | RawEvent::DeliveredToFallback(..)
+            | RawEvent::Approved(..)
+            | RawEvent::VoteLocked(..)
+            | RawEvent::VoteUnlocked(..)
+            | RawEvent::OwnershipProposed(..)
+            | RawEvent::OwnerRotationBegun(..)
+            | RawEvent::OwnerRotationCancelled(..)
+            | RawEvent::AdminActionProposed(..)
+            | RawEvent::AdminActionApproved(..)
+            | RawEvent::AdminActionExecuted(..)
+            | RawEvent::InvoicePaid(..)
+            | RawEvent::SwappedSigned(..)
+            | RawEvent::CommunityFundSet(..) => EVENTS_DETAILED,
+        }
+    }
+
+    /// イベント番号の払い出し
+    /// 現在の番号を返し、次の番号へ進める
+    fn _next_event_seq() -> u64 {
+        let seq = Self::event_seq();
+        <EventSeq<T>>::put(seq.wrapping_add(1));
+        seq
+    }
+
+    /// アカウントの操作番号の払い出し
+    fn _next_account_nonce(who: &T::AccountId) -> u64 {
+        let nonce = Self::account_tx_nonce(who);
+        <AccountTxNonce<T>>::insert(who, nonce.wrapping_add(1));
+        nonce
+    }
+
+    /// `from` から `to` への送信を止めている制限 (RESTRICTION_* のビットの和)
+    /// 0 なら制限はない。残高と投票ロックは見ない
+    pub fn transfer_restrictions(from: &T::AccountId, to: &T::AccountId, asset_id: T::Hash) -> u32 {
+        if !<Assets<T>>::exists(asset_id) {
+            return RESTRICTION_UNKNOWN_ASSET;
+        }
+        let requires_allowlist = Self::requires_allowlist(asset_id);
+        [
+            (Self::frozen(asset_id), RESTRICTION_FROZEN),
+            (Self::is_reserved_account(to), RESTRICTION_RESERVED_RECIPIENT),
+            (Self::asset_opt_out((to.clone(), asset_id)), RESTRICTION_OPTED_OUT),
+            (requires_allowlist && !Self::is_allowlisted(asset_id, from), RESTRICTION_SENDER_NOT_ALLOWLISTED),
+            (requires_allowlist && !Self::is_allowlisted(asset_id, to), RESTRICTION_RECIPIENT_NOT_ALLOWLISTED),
+            (Self::_ensure_destination_allowed(asset_id, to).is_err(), RESTRICTION_DESTINATION_NOT_ALLOWED),
+            (Self::_ensure_cooldown_passed(from, asset_id).is_err(), RESTRICTION_COOLDOWN),
+            (Self::require_memo(asset_id), RESTRICTION_MEMO_REQUIRED),
+        ]
+            .iter()
+            .filter(|&&(active, _)| active)
+            .fold(0, |bits, &(_, bit)| bits | bit)
+    }
+
+    /// atomic_swap_signed で相手が署名する内容
+    /// 提出者も含めるため、他のアカウントは同じ署名で交換できない
+    pub fn swap_payload(
+        submitter: &T::AccountId,
+        counterparty: &T::AccountId,
+        give_asset: T::Hash,
+        give_qty: u64,
+        want_asset: T::Hash,
+        want_qty: u64,
+        nonce: u64,
+    ) -> Vec<u8> {
+        (SWAP_CONTEXT, submitter, counterparty, (give_asset, give_qty), (want_asset, want_qty), nonce).encode()
+    }
+
+    /// 送信ポリシーの確認
+    /// 送信者側 (_ensure_sender_allowed) と受信者側 (_ensure_recipient_allowed) をまとめて確認する
+    /// アカウント間の送信 (sendasset 系・send_asset_batch・transfer_from・承諾待ち送信の作成・exit_all・
+    /// atomic_swap_signed・Fungible::transfer) はこの関数を通す
+    /// モジュール管理アカウントとの間の移動は、利用者側の片方だけを確認する
+    /// - 利用者からモジュール管理アカウントへ (lock_for_bridge・wrap・create_airdrop): 送信者側
+    /// - モジュール管理アカウントから利用者へ (release_from_bridge・unwrap・claim_airdrop・accept_transfer): 受信者側
+    /// - 承諾待ち送信の取り消しと期限切れの返却は、元の送信者へ戻すだけのため確認しない
+    /// 凍結と残高・投票ロックは _send_asset が確認する
+    /// メモ必須の資産は、メモ付きで送る経路 (`with_memo` が true) でなければ送れない
+    fn _ensure_transfer_allowed(from: &T::AccountId, to: &T::AccountId, asset_id: T::Hash, with_memo: bool) -> Result {
+        ensure!(with_memo || !Self::require_memo(asset_id), "Memo required");
+        Self::_ensure_sender_allowed(from, asset_id)?;
+        Self::_ensure_recipient_allowed(to, asset_id)
+    }
+
+    /// 送信者側の送信ポリシーの確認 (送信間隔・許可リスト)
+    fn _ensure_sender_allowed(from: &T::AccountId, asset_id: T::Hash) -> Result {
+        Self::_ensure_cooldown_passed(from, asset_id)?;
+        ensure!(!Self::requires_allowlist(asset_id) || Self::is_allowlisted(asset_id, from),
+            "Sender is not allowlisted for this asset");
+        Ok(())
+    }
+
+    /// 受信者側の送信ポリシーの確認 (モジュール管理アカウント・受け取り拒否・許可リスト・送信先制限)
+    fn _ensure_recipient_allowed(to: &T::AccountId, asset_id: T::Hash) -> Result {
+        // モジュール管理アカウントへの直接送信は内部の記録と食い違うため禁止
+        ensure!(!Self::is_reserved_account(to), "Cannot send to a reserved account");
+        ensure!(!Self::asset_opt_out((to.clone(), asset_id)), "Recipient opted out of this asset");
+        ensure!(!Self::requires_allowlist(asset_id) || Self::is_allowlisted(asset_id, to),
+            "Recipient is not allowlisted for this asset");
+        Self::_ensure_destination_allowed(asset_id, to)
+    }
+
+    /// 送信間隔の確認
+    fn _ensure_cooldown_passed(who: &T::AccountId, asset_id: T::Hash) -> Result {
+        let cooldown = Self::cooldown(asset_id);
+        if cooldown.is_zero() {
+            return Ok(());
+        }
+        if let Some(last) = Self::last_send((who.clone(), asset_id)) {
+            let now = <system::Module<T>>::block_number();
+            ensure!(now - last >= cooldown, "Transfer cooldown has not passed");
+        }
+        Ok(())
+    }
+
+    /// 焼却できるか
+    pub fn is_burnable(asset_id: T::Hash) -> bool {
+        !Self::non_burnable(asset_id)
+    }
+
+    /// 許可リストに載っているか
+    /// 発行者は常に許可されている
+    pub fn is_allowlisted(asset_id: T::Hash, who: &T::AccountId) -> bool {
+        Self::owner_of(asset_id).as_ref() == Some(who) || Self::allowlisted((asset_id, who.clone()))
+    }
+
+    /// 送信先制限の確認
+    /// 送信先制限のない資産は常に成功する。発行者への送信は常に許可する
+    fn _ensure_destination_allowed(asset_id: T::Hash, to: &T::AccountId) -> Result {
+        if !Self::restrict_destinations(asset_id) {
+            return Ok(());
+        }
+        ensure!(
+            Self::owner_of(asset_id).as_ref() == Some(to) || Self::allowed_destination((asset_id, to.clone())),
+            "Destination is not allowed for this asset"
+        );
+        Ok(())
+    }
+
+    /// 使用許可量の更新（内部処理）
+    /// Allowances の書き換えは必ずこの関数を通し、SpenderApprovals を合わせて更新する
+    /// 新しく許可を記録する場合の件数の確認は呼び出し側で行う
+    fn _set_allowance(owner: &T::AccountId, spender: &T::AccountId, asset_id: T::Hash, qty: u64) {
+        let key = (owner.clone(), spender.clone(), asset_id);
+        let entry = (owner.clone(), asset_id);
+        if qty == 0 {
+            <Allowances<T>>::remove(&key);
+            <SpenderApprovals<T>>::mutate(spender, |approvals| approvals.retain(|e| *e != entry));
+        } else {
+            if !<Allowances<T>>::exists(&key) {
+                <SpenderApprovals<T>>::mutate(spender, |approvals| approvals.push(entry));
+            }
+            <Allowances<T>>::insert(&key, qty);
+        }
+    }
+
+    /// 使用者が許可を受けている (所有者, 資産 ID, 許可量) の一覧
+    pub fn approvals_for_spender(spender: &T::AccountId) -> Vec<(T::AccountId, T::Hash, u64)> {
+        Self::spender_approvals(spender)
+            .into_iter()
+            .map(|(owner, asset_id)| {
+                let qty = Self::allowance((owner.clone(), spender.clone(), asset_id));
+                (owner, asset_id, qty)
+            })
+            .collect()
+    }
+
+    /// 受信者側の制限で受け取れないか
+    /// 受け取り拒否・許可リスト・送信先制限のみを見る (送信者側の制限は含まない)
+    fn _recipient_rejects(asset_id: T::Hash, to: &T::AccountId) -> bool {
+        Self::asset_opt_out((to.clone(), asset_id))
+            || (Self::requires_allowlist(asset_id) && !Self::is_allowlisted(asset_id, to))
+            || Self::_ensure_destination_allowed(asset_id, to).is_err()
+    }
+
+    /// 送信ブロックの記録
+    fn _record_send(who: &T::AccountId, asset_id: T::Hash) {
+        if !Self::cooldown(asset_id).is_zero() {
+            <LastSend<T>>::insert((who.clone(), asset_id), <system::Module<T>>::block_number());
+        }
+    }
+
+    /// exit_all で送れるか
+    /// _send_asset 自身が確認しない送信ポリシーを見る
+    fn _can_exit(from: &T::AccountId, to: &T::AccountId, asset_id: T::Hash) -> bool {
+        Self::_ensure_transfer_allowed(from, to, asset_id, false).is_ok()
+    }
+
+    /// SentAsset イベント
+    /// イベント番号は送信イベントが無効な資産でも払い出す
+    /// 送信者の操作番号を返す
+    fn _deposit_sent_asset(from: T::AccountId, to: T::AccountId, asset_id: T::Hash, qty: u64) -> u64 {
+        let seq = Self::_next_event_seq();
+        let nonce = Self::_next_account_nonce(&from);
+        if Self::events_enabled(asset_id) {
+            Self::_deposit_event(RawEvent::SentAsset(from, to, asset_id, qty, seq, nonce));
+        }
+        nonce
+    }
+
+    /// 発行者の権限操作の記録
+    fn _log_owner_action(asset_id: T::Hash, action: &[u8]) {
+        let now = <system::Module<T>>::block_number();
+        <OwnerActionLog<T>>::mutate(asset_id, |log| {
+            log.push((now, action.to_vec()));
+            if log.len() > MAX_OWNER_ACTIONS {
+                let excess = log.len() - MAX_OWNER_ACTIONS;
+                log.drain(..excess);
+            }
+        });
+    }
+
+    /// 資産の監査
+    /// asset-audit 機能を有効にしたテストでのみ、所有量・発行済量を変える処理の後で verify_asset を確かめ、
+    /// 整合性が崩れた時点で止める。それ以外では何もしない
+    fn _audit(asset_id: T::Hash) {
+        #[cfg(all(test, feature = "asset-audit"))]
+        debug_assert!(Self::verify_asset(asset_id), "asset audit failed");
+        #[cfg(not(all(test, feature = "asset-audit")))]
+        let _ = asset_id;
+    }
+
+    /// 発行量履歴の記録
+    /// 同じブロック内の変更は最後の値で上書きする
+    fn _record_supply(asset_id: T::Hash, total: u64) {
+        let now = <system::Module<T>>::block_number();
+        <SupplyCheckpoints<T>>::mutate(asset_id, |history| {
+            match history.last_mut() {
+                Some(last) if last.0 == now => last.1 = total,
+                _ => history.push((now, total)),
+            }
+            if history.len() > MAX_SUPPLY_CHECKPOINTS {
+                let excess = history.len() - MAX_SUPPLY_CHECKPOINTS;
+                history.drain(..excess);
+            }
+        });
+    }
+
+    /// オリジナル資産発行（内部処理）
+    /// 資産 ID は (random_seed, 発行者, Nonce) から生成する
+    /// 発行した資産 ID を返す
+    fn _issue(sender: T::AccountId, name: Vec<u8>, issue_qty: u64, open: bool) -> rstd::result::Result<T::Hash, &'static str> {
+        let random_hash = Self::_next_asset_id(&sender);
+
+        Self::_issue_with_id(sender, random_hash, name, issue_qty, open)?;
+
+        <Nonce<T>>::mutate(|n| *n += 1);
+
+        Ok(random_hash)
+    }
+
+    /// 次に `sender` が _issue で発行する資産の ID
+    fn _next_asset_id(sender: &T::AccountId) -> T::Hash {
+        let nonce = <Nonce<T>>::get();
+        (<system::Module<T>>::random_seed(), sender, nonce)
+            .using_encoded(<T as system::Trait>::Hashing::hash)
+    }
+
+    /// 所有者変更（内部処理）
+    /// OwnedAssets* を旧所有者から新所有者へ移す。所有量は変わらない
+    fn _transfer_ownership(asset_id: T::Hash, from: T::AccountId, to: T::AccountId) -> Result {
+        // 同じアカウントでは索引を取り除いてから末尾に足すことになり、件数がずれる
+        ensure!(from != to, "You already own this asset");
+
+        let owned_asset_count_from = Self::owned_asset_count(&from);
+        let owned_asset_count_to = Self::owned_asset_count(&to);
+
+        let new_owned_asset_count_to = owned_asset_count_to.checked_add(1)
+            .ok_or("Overflow adding a new Asset to account balance")?;
+        let new_owned_asset_count_from = owned_asset_count_from.checked_sub(1)
+            .ok_or("Underflow removing an Asset from account balance")?;
+
+        // --------------------- 更新
+        // "Swap and pop"
+        let asset_index = <OwnedAssetsIndex<T>>::get(asset_id);
+        if asset_index != new_owned_asset_count_from {
+            let last_asset_id = <OwnedAssetsArray<T>>::get((from.clone(), new_owned_asset_count_from));
+            <OwnedAssetsArray<T>>::insert((from.clone(), asset_index), last_asset_id);
+            <OwnedAssetsIndex<T>>::insert(last_asset_id, asset_index);
+        }
+        <OwnedAssetsArray<T>>::remove((from.clone(), new_owned_asset_count_from));
+        <OwnedAssetsCount<T>>::insert(&from, new_owned_asset_count_from);
+
+        <AssetOwner<T>>::insert(asset_id, &to);
+        <OwnedAssetsArray<T>>::insert((to.clone(), owned_asset_count_to), asset_id);
+        <OwnedAssetsIndex<T>>::insert(asset_id, owned_asset_count_to);
+        <OwnedAssetsCount<T>>::insert(&to, new_owned_asset_count_to);
+        // --------------------- 更新 --- ここまで
+
+        Ok(())
+    }
+
+    /// 資産 ID を指定したオリジナル資産発行（内部処理）
+    fn _issue_with_id(sender: T::AccountId, asset_id: T::Hash, name: Vec<u8>, issue_qty: u64, open: bool) -> Result {
+        // 最小発行量確認
+        ensure!(issue_qty >= Self::min_issue_qty(), "Issue quantity is below the minimum");
+        // 資産名確認
+        ensure!(Self::name_policy().allows(&name), "Asset name contains disallowed characters");
+        // ブロックあたりの発行数確認
+        let issues_this_block = Self::issues_this_block();
+        ensure!(issues_this_block < Self::max_issues_per_block(), "Too many issues in this block");
+
+        // 発行済資産数
+        let owned_asset_count = Self::owned_asset_count(&sender);
+        // 発行済資産数 + 1
+        let new_owned_asset_count = owned_asset_count.checked_add(1)
+            .ok_or("Overflow adding a new Asset to account balance")?;
+
+        // 全資産数
+        let all_asset_count = Self::all_asset_count();
+        // 全資産数 + 1
+        let new_all_asset_count = all_asset_count.checked_add(1)
+            .ok_or("Overflow adding a new Asset to total supply")?;
+
+        ensure!(!<AssetOwner<T>>::exists(asset_id), "Asset already exists");
+
+        // オリジナル資産情報
+        let new_asset = Asset {
+            id: asset_id,
+            name: name,
+            open: open,
+        };
+
+        // 資産発行
+        Self::my_asset_count(&sender).checked_add(1)
+            .ok_or("Overflow adding a new My Asset to total supply")?;
+
+
+        // --------------------- 更新
+        // 更新する値が正常であることが確認済みであることが必須!
+
+        <Assets<T>>::insert(asset_id, new_asset);
+        <AssetOwner<T>>::insert(asset_id, &sender);
+
+        <AllAssetsArray<T>>::insert(all_asset_count, asset_id);
+        <AllAssetsCount<T>>::put(new_all_asset_count);
+        <AllAssetsIndex<T>>::insert(asset_id, all_asset_count);
+
+        <OwnedAssetsArray<T>>::insert((sender.clone(), owned_asset_count), asset_id);
+        <OwnedAssetsCount<T>>::insert(&sender, new_owned_asset_count);
+        <OwnedAssetsIndex<T>>::insert(asset_id, owned_asset_count);
+
+        <TotalIssuedAssets<T>>::insert(asset_id, issue_qty);
+        Self::_record_supply(asset_id, issue_qty);
+
+        <IssuesThisBlock<T>>::put(issues_this_block + 1);
+
+        // 新しい資産のため登録数の確認は済んでおり、失敗しない
+        Self::_set_balance(&sender, asset_id, issue_qty)?;
+        Self::_audit(asset_id);
+
+        // --------------------- 更新 --- ここまで
+        
+        let nonce = Self::_next_account_nonce(&sender);
+        Self::_deposit_event(RawEvent::Issued(sender, asset_id, Self::_next_event_seq(), nonce));
+
+        Ok(())
+    }
+
+    /// 所有量 0 の資産の登録の削除（内部処理）
+    /// MyAssets* と保有者配列から "Swap and pop" で取り除く。所有量が 0 でなければ何もしない
+    /// 再び受け取ると _set_balance が新しく登録する
+    fn _remove_my_asset(who: &T::AccountId, asset_id: T::Hash) {
+        let key = (who.clone(), asset_id);
+        if Self::my_asset_balance(&key) > 0 || !<MyAssetsIndex<T>>::exists(&key) {
+            return;
+        }
+
+        // 登録があるため、件数は 1 以上
+        let last = Self::my_asset_count(who) - 1;
+        let index = <MyAssetsIndex<T>>::get(&key);
+        if index != last {
+            let last_asset_id = Self::my_asset_by_index((who.clone(), last));
+            <MyAssetsArray<T>>::insert((who.clone(), index), last_asset_id);
+            <MyAssetsIndex<T>>::insert((who.clone(), last_asset_id), index);
+        }
+        <MyAssetsArray<T>>::remove((who.clone(), last));
+        <MyAssetsCount<T>>::insert(who, last);
+        <MyAssetsIndex<T>>::remove(&key);
+        <MyAssetBalances<T>>::remove(&key);
+
+        let holder_key = (asset_id, who.clone());
+        if <AssetHoldersIndex<T>>::exists(&holder_key) {
+            let last = Self::asset_holders_count(asset_id) - 1;
+            let index = <AssetHoldersIndex<T>>::get(&holder_key);
+            if index != last {
+                let last_holder = Self::asset_holder_by_index((asset_id, last));
+                <AssetHoldersArray<T>>::insert((asset_id, index), &last_holder);
+                <AssetHoldersIndex<T>>::insert((asset_id, last_holder), index);
+            }
+            <AssetHoldersArray<T>>::remove((asset_id, last));
+            <AssetHoldersCount<T>>::insert(asset_id, last);
+            <AssetHoldersIndex<T>>::remove(&holder_key);
+        }
+    }
+
+    /// 所有量の更新（内部処理）
+    /// MyAssetBalances の書き換えは必ずこの関数を通す
+    /// - 初めて所有する場合は MyAssets* に登録する
+    /// - 0 をまたぐ変更で HolderCount を 1 だけ増減する
+    /// 失敗しうる計算はすべて更新より前に行う
+    fn _set_balance(who: &T::AccountId, asset_id: T::Hash, balance: u64) -> Result {
+        let key = (who.clone(), asset_id);
+        let old_balance = Self::my_asset_balance(&key);
+
+        // 初めて所有する場合の登録位置
+        let new_entry = if <MyAssetsIndex<T>>::exists(&key) {
+            None
+        } else {
+            let count = Self::my_asset_count(who);
+            let new_count = count.checked_add(1)
+                .ok_or("Overflow adding a new My Asset to total supply")?;
+            let holders = Self::asset_holders_count(asset_id);
+            let new_holders = holders.checked_add(1)
+                .ok_or("Overflow adding to the asset holders")?;
+            Some((count, new_count, holders, new_holders))
+        };
+
+        // 保有者数
+        let holder_count = Self::holder_count(asset_id);
+        let new_holder_count = if old_balance == 0 && balance > 0 {
+            holder_count.checked_add(1).ok_or("Overflow adding a holder")?
+        } else if old_balance > 0 && balance == 0 {
+            holder_count.checked_sub(1).ok_or("Underflow removing a holder")?
+        } else {
+            holder_count
+        };
+
+        // --------------------- 更新
+        if let Some((count, new_count, holders, new_holders)) = new_entry {
+            <MyAssetsArray<T>>::insert((who.clone(), count), asset_id);
+            <MyAssetsCount<T>>::insert(who, new_count);
+            <MyAssetsIndex<T>>::insert(&key, count);
+            <AssetHoldersArray<T>>::insert((asset_id, holders), who);
+            <AssetHoldersCount<T>>::insert(asset_id, new_holders);
+            <AssetHoldersIndex<T>>::insert((asset_id, who.clone()), holders);
+        }
+        <MyAssetBalances<T>>::insert(&key, balance);
+        if new_holder_count != holder_count {
+            <HolderCount<T>>::insert(asset_id, new_holder_count);
+        }
+        // --------------------- 更新 --- ここまで
+
+        Ok(())
+    }
+
+    /// コミュニティ基金への分配量
+    /// 基金が設定されており分配量が 0 より大きい場合に (基金アカウント, 分配量) を返す
+    fn _community_split(asset_id: T::Hash, qty: u64) -> Option<(T::AccountId, u64)> {
+        let fund = Self::community_fund(asset_id)?;
+        let bps = u64::from(Self::community_bps(asset_id));
+        // bps <= 10000 のため cut <= qty
+        let cut = (u128::from(qty) * u128::from(bps) / u128::from(BPS_DENOMINATOR)) as u64;
+        if cut == 0 {
+            None
+        } else {
+            Some((fund, cut))
+        }
+    }
+
+    /// 送信時の控除
+    /// (コミュニティ基金への分配, 焼却量) を返す。分配は _community_split と同じ
+    /// 自分自身への送信は残高が動かないため控除しない
+    fn _transfer_deductions(from: &T::AccountId, to: &T::AccountId, asset_id: T::Hash, qty: u64)
+        -> rstd::result::Result<(Option<(T::AccountId, u64)>, u64), &'static str>
+    {
+        if from == to {
+            return Ok((None, 0));
+        }
+        let split = Self::_community_split(asset_id, qty);
+        let cut = split.as_ref().map_or(0, |(_, cut)| *cut);
+        // 焼却量 (burn_bps <= 10000 のため qty を超えない)
+        let burn = (u128::from(qty) * u128::from(Self::burn_bps(asset_id)) / u128::from(BPS_DENOMINATOR)) as u64;
+        // 分配と焼却の合計は qty を超えられない
+        cut.checked_add(burn)
+            .and_then(|deducted| if deducted <= qty { Some(deducted) } else { None })
+            .ok_or("Transfer deductions exceed the amount")?;
+        Ok((split, burn))
+    }
+
+    /// 控除付きの資産送信の確認（内部処理）
+    /// _send_with_deductions が更新前に行う確認。複数の送信をまとめて行う呼び出し側が、更新の前に使う
+    /// 控除 (_transfer_deductions の結果) を返す
+    fn _ensure_can_send_with_deductions(from: &T::AccountId, to: &T::AccountId, asset_id: T::Hash, qty: u64)
+        -> rstd::result::Result<(Option<(T::AccountId, u64)>, u64), &'static str>
+    {
+        let (split, burn) = Self::_transfer_deductions(from, to, asset_id, qty)?;
+        // 送信者は qty の全量を手放すため、受信者へ全量を送る場合と同じ確認で足りる
+        Self::_ensure_can_send(from, to, asset_id, qty)?;
+        if let Some((fund, cut)) = split.as_ref() {
+            if fund != from && fund != to {
+                Self::_ensure_can_send(from, fund, asset_id, *cut)?;
+            }
+        }
+        // 受信者と基金がどちらも新しい保有者になる場合は、2 件の送信を合わせて確認する
+        let cut = split.as_ref().map_or(0, |(_, cut)| *cut);
+        let mut legs = vec![(to.clone(), qty - cut - burn)];
+        if let Some((fund, cut)) = split.as_ref() {
+            legs.push((fund.clone(), *cut));
+        }
+        Self::_ensure_holder_capacity(from, asset_id, &legs)?;
+        if burn > 0 {
+            ensure!(Self::is_burnable(asset_id), "Asset is not burnable");
+        }
+        Ok((split, burn))
+    }
+
+    /// 保有者数の上限の確認（内部処理）
+    /// `legs` は `from` からの送信 (送信先, 量) を、_send_asset で送る順に並べたもの
+    /// 各送信で _send_asset が行う上限の確認を、前の送信の結果を反映しながら更新前にまとめて行う
+    /// (送信ごとに確認すると、後の送信が上限を超えたときに前の送信だけが書き込まれる)
+    fn _ensure_holder_capacity(from: &T::AccountId, asset_id: T::Hash, legs: &[(T::AccountId, u64)]) -> Result {
+        let max_holders = Self::max_holders(asset_id);
+        if max_holders == 0 {
+            return Ok(());
+        }
+        let mut holders = Self::holder_count(asset_id);
+        let mut balance = Self::my_asset_balance((from.clone(), asset_id));
+        // 前の送信で受け取った送信先
+        let mut credited: Vec<&T::AccountId> = Vec::new();
+        for (to, qty) in legs.iter() {
+            if to == from || *qty == 0 {
+                continue;
+            }
+            if !credited.contains(&to) && Self::my_asset_balance((to.clone(), asset_id)) == 0 {
+                // 全量送信なら送信者が保有者から外れるため、保有者数は変わらない
+                let leaving = if balance == *qty { 1 } else { 0 };
+                ensure!(holders + 1 - leaving <= max_holders, "Max holders reached");
+                holders += 1;
+            }
+            credited.push(to);
+            let new_balance = balance.saturating_sub(*qty);
+            if balance > 0 && new_balance == 0 {
+                holders = holders.saturating_sub(1);
+            }
+            balance = new_balance;
+        }
+        Ok(())
+    }
+
+    /// 控除付きの資産送信（内部処理）
+    /// `qty` からコミュニティ基金への分配と焼却を差し引いた残りを `to` へ送り、(受信量, 焼却量) を返す
+    /// 利用者の送信 (sendasset 系・send_asset_batch・transfer_from・承諾待ち送信の作成・exit_all・
+    /// atomic_swap_signed・Fungible::transfer) はすべてこの関数を通す
+    /// モジュール管理アカウントとの間の移動は、量をそのまま動かすため _send_asset を使う
+    /// (wrap のロックは例外で、ラップ資産での送信で控除を避けられないよう、この関数で差し引く)
+    /// CommunityFunded はこの関数が発行し、SentAsset と Burned は呼び出し側が _deposit_sent_with_burn で発行する
+    fn _send_with_deductions(from: &T::AccountId, to: &T::AccountId, asset_id: T::Hash, qty: u64)
+        -> rstd::result::Result<(u64, u64), &'static str>
+    {
+        let (net, split, burn) = Self::_move_with_deductions(from, to, asset_id, qty)?;
+        if let Some((fund, cut)) = split {
+            Self::_deposit_event(RawEvent::CommunityFunded(from.clone(), fund, asset_id, cut));
+        }
+
+        Ok((net, burn))
+    }
+
+    /// 控除付きの資産移動（内部処理）
+    /// _send_with_deductions のうち、イベントを発行しない部分。(受信量, コミュニティ基金への分配, 焼却量) を返す
+    /// CommunityFunded をまとめて発行する呼び出し側 (send_asset_batch の summarize) が直接使う
+    fn _move_with_deductions(from: &T::AccountId, to: &T::AccountId, asset_id: T::Hash, qty: u64)
+        -> rstd::result::Result<(u64, Option<(T::AccountId, u64)>, u64), &'static str>
+    {
+        // 複数回の送信・焼却の途中で失敗しないよう、先に確認する
+        let (split, burn) = Self::_ensure_can_send_with_deductions(from, to, asset_id, qty)?;
+        let cut = split.as_ref().map_or(0, |(_, cut)| *cut);
+        let net = qty - cut - burn;
+
+        Self::_send_asset(from.clone(), to.clone(), asset_id, net)?;
+        if let Some((fund, cut)) = split.as_ref() {
+            Self::_send_asset(from.clone(), fund.clone(), asset_id, *cut)?;
+        }
+        if burn > 0 {
+            Self::_burn(from.clone(), asset_id, burn)?;
+        }
+
+        Ok((net, split, burn))
+    }
+
+    /// 控除付きの送信の SentAsset と Burned イベント
+    /// 送信の一部として焼却したため、Burned の操作番号は送信と同じ
+    fn _deposit_sent_with_burn(from: T::AccountId, to: T::AccountId, asset_id: T::Hash, net: u64, burn: u64) {
+        let nonce = Self::_deposit_sent_asset(from.clone(), to, asset_id, net);
+        if burn > 0 {
+            Self::_deposit_event(RawEvent::Burned(from, asset_id, burn, Self::_next_event_seq(), nonce));
+        }
+    }
+
+    /// 資産送信（内部処理）
+    /// sendasset の本体。送信制限の確認と控除を行う
+    /// `with_memo` はメモ付きの送信 (send_asset_with_memo) であれば true
+    fn _sendasset(sender: T::AccountId, to: T::AccountId, asset_id: T::Hash, qty: u64, with_memo: bool) -> Result {
+        Self::_ensure_can_sendasset(&sender, &to, asset_id, qty, with_memo)?;
+
+        let (net, burn) = Self::_send_with_deductions(&sender, &to, asset_id, qty)?;
+        Self::_record_send(&sender, asset_id);
+
+        Self::_deposit_sent_with_burn(sender, to, asset_id, net, burn);
+
+        Ok(())
+    }
+
+    /// 資産送信の確認（内部処理）
+    /// _sendasset が更新前に行う確認。送信の前に別の更新を行う呼び出し側 (sponsored_transfer) が使う
+    fn _ensure_can_sendasset(sender: &T::AccountId, to: &T::AccountId, asset_id: T::Hash, qty: u64, with_memo: bool) -> Result {
+        Self::_ensure_transfer_allowed(sender, to, asset_id, with_memo)?;
+
+        // 厳格モード: 新しい保有者は 1 つまで
+        if Self::strict_holder_mode() {
+            let (split, burn) = Self::_transfer_deductions(sender, to, asset_id, qty)?;
+            let cut = split.as_ref().map_or(0, |(_, cut)| *cut);
+            let net = qty - cut - burn;
+            let mut new_holders = 0;
+            if to != sender && net > 0 && Self::my_asset_balance((to.clone(), asset_id)) == 0 {
+                new_holders += 1;
+            }
+            if let Some((fund, cut)) = split.as_ref() {
+                if fund != sender && fund != to && *cut > 0 && Self::my_asset_balance((fund.clone(), asset_id)) == 0 {
+                    new_holders += 1;
+                }
+            }
+            ensure!(new_holders <= 1, "Too many new holders in one transfer");
+        }
+
+        Self::_ensure_can_send_with_deductions(sender, to, asset_id, qty)?;
+        Ok(())
+    }
+
+    /// 承諾待ち送信の作成（内部処理）
+    /// propose_transfer と propose_expiring_transfer で共有する。送信 ID を返す
+    fn _propose_transfer(sender: T::AccountId, to: T::AccountId, asset_id: T::Hash, qty: u64) -> rstd::result::Result<T::Hash, &'static str> {
+        ensure!(to != sender, "You can not propose a transfer to yourself");
+        Self::_ensure_transfer_allowed(&sender, &to, asset_id, false)?;
+
+        // 送信 ID 生成
+        let nonce = <Nonce<T>>::get();
+        let transfer_id = (<system::Module<T>>::random_seed(), &sender, nonce)
+            .using_encoded(<T as system::Trait>::Hashing::hash);
+        ensure!(!<PendingTransfers<T>>::exists(transfer_id), "Pending transfer already exists");
+        ensure!(Self::escrows_of(&sender).len() < MAX_PENDING_PER_ACCOUNT, "Too many pending transfers");
+        ensure!(Self::escrows_of(&to).len() < MAX_PENDING_PER_ACCOUNT, "Too many pending transfers");
+
+        // 控除は預けるときに差し引き、残りを預ける
+        let (net, burn) = Self::_send_with_deductions(&sender, &Self::escrow_account(), asset_id, qty)?;
+
+        // --------------------- 更新
+        <PendingTransfers<T>>::insert(transfer_id, PendingTransfer {
+            from: sender.clone(),
+            to: to.clone(),
+            asset_id,
+            qty: net,
+        });
+        <EscrowsByParty<T>>::mutate(&sender, |ids| ids.push(transfer_id));
+        <EscrowsByParty<T>>::mutate(&to, |ids| ids.push(transfer_id));
+        <Nonce<T>>::mutate(|n| *n += 1);
+        Self::_record_send(&sender, asset_id);
+        // --------------------- 更新 --- ここまで
+
+        if burn > 0 {
+            let nonce = Self::_next_account_nonce(&sender);
+            Self::_deposit_event(RawEvent::Burned(sender.clone(), asset_id, burn, Self::_next_event_seq(), nonce));
+        }
+        Self::_deposit_event(RawEvent::TransferProposed(transfer_id, sender, to, asset_id, net));
+
+        Ok(transfer_id)
+    }
+
+    /// 承諾待ち送信の承諾（内部処理）
+    /// accept_transfer と acknowledge_receipt で共有する
+    /// 期限付き送信は期限のブロックまで承諾できる
+    fn _accept_transfer(sender: T::AccountId, transfer_id: T::Hash) -> Result {
+        let transfer = Self::pending_transfer(transfer_id).ok_or("This pending transfer does not exist")?;
+        ensure!(transfer.to == sender, "You are not the recipient of this transfer");
+        if let Some(expiry) = Self::transfer_expiry(transfer_id) {
+            ensure!(<system::Module<T>>::block_number() <= expiry, "This transfer has expired");
+        }
+        Self::_ensure_recipient_allowed(&sender, transfer.asset_id)?;
+
+        // --------------------- 更新
+        Self::_send_asset(Self::escrow_account(), sender.clone(), transfer.asset_id, transfer.qty)?;
+        Self::_remove_pending_transfer(transfer_id, &transfer);
+        // --------------------- 更新 --- ここまで
+
+        Self::_deposit_sent_asset(transfer.from, sender, transfer.asset_id, transfer.qty);
+        Self::_deposit_event(RawEvent::TransferAccepted(transfer_id));
+
+        Ok(())
+    }
+
+    /// 期限切れの送信の返却（内部処理）
+    /// `n` のブロックで期限を迎えた、受信確認されていない送信を送信者へ戻す
+    /// 凍結などで戻せなかった送信は承諾待ちのまま残り、送信者が cancel_transfer で取り消せる
+    fn _expire_transfers(n: T::BlockNumber) {
+        for transfer_id in <ExpiringTransfers<T>>::take(n) {
+            let transfer = match Self::pending_transfer(transfer_id) {
+                Some(transfer) => transfer,
+                None => continue,
+            };
+            if Self::_send_asset(Self::escrow_account(), transfer.from.clone(), transfer.asset_id, transfer.qty).is_ok() {
+                Self::_remove_pending_transfer(transfer_id, &transfer);
+                Self::_deposit_event(RawEvent::TransferExpired(transfer_id));
+            }
+        }
+    }
+
+    /// 承諾待ち送信の削除（内部処理）
+    /// 送信者・受信者の EscrowsByParty と、期限付き送信の期限からも取り除く
+    fn _remove_pending_transfer(transfer_id: T::Hash, transfer: &PendingTransfer<T::AccountId, T::Hash>) {
+        <PendingTransfers<T>>::remove(transfer_id);
+        <EscrowsByParty<T>>::mutate(&transfer.from, |ids| ids.retain(|id| *id != transfer_id));
+        <EscrowsByParty<T>>::mutate(&transfer.to, |ids| ids.retain(|id| *id != transfer_id));
+        if let Some(expiry) = <TransferExpiry<T>>::take(transfer_id) {
+            <ExpiringTransfers<T>>::mutate(expiry, |ids| ids.retain(|id| *id != transfer_id));
+        }
+    }
+
+    /// 委員会操作の実行（内部処理）
+    /// 発行者の権限で対応する関数を呼ぶため、発行者が直接実行した場合と同じ確認を受ける
+    fn _execute_admin_action(proposal: &AdminProposal<T::AccountId, T::Hash>) -> Result {
+        let owner = Self::owner_of(proposal.asset_id).ok_or("No owner for this asset")?;
+        let origin: T::Origin = system::RawOrigin::Signed(owner).into();
+        let asset_id = proposal.asset_id;
+        match proposal.action {
+            AdminAction::Mint(qty) => Self::issuemore(origin, asset_id, qty),
+            AdminAction::SetMaxHolders(max) => Self::set_max_holders(origin, asset_id, max),
+            AdminAction::SetMaxMintPerCall(max) => Self::set_max_mint_per_call(origin, asset_id, max),
+            AdminAction::SetRequiresAllowlist(required) => Self::set_requires_allowlist(origin, asset_id, required),
+        }
+    }
+
+    /// 所有している資産として登録されているかの確認
+    /// 一度も所有したことのない発行者 (所有権を譲り受けた場合など) には、
+    /// 資産が存在しないのではなく所有量がないことを伝える
+    fn _ensure_registered_holder(who: &T::AccountId, asset_id: T::Hash) -> Result {
+        if <MyAssetsIndex<T>>::exists((who.clone(), asset_id)) {
+            return Ok(());
+        }
+        if Self::owner_of(asset_id).as_ref() == Some(who) {
+            return Err("You hold none of this asset");
+        }
+        Err("This asset does not exist")
+    }
+
+    /// 資産焼却（内部処理）
+    /// 所有量と発行済量の両方を減らす
+    fn _burn(who: T::AccountId, asset_id: T::Hash, qty: u64) -> Result {
+        ensure!(<MyAssetsIndex<T>>::exists((who.clone(), asset_id)), "This asset does not exist");
+        ensure!(Self::is_burnable(asset_id), "Asset is not burnable");
+
+        let my_asset_balance = Self::my_asset_balance((who.clone(), asset_id));
+        let new_my_asset_balance = my_asset_balance.checked_sub(qty)
+            .ok_or("Your asset is less than you want to burn")?;
+        ensure!(Self::spendable_balance(&who, asset_id) >= qty, "Your asset is locked for voting");
+
+        let total_issued_asset = Self::total_issued_asset(asset_id);
+        let new_total_issued_asset = total_issued_asset.checked_sub(qty)
+            .ok_or("Underflow burning the total issued asset")?;
+
+        // --------------------- 更新
+        // 更新する値が正常であることが確認済みであることが必須!
+        Self::_set_balance(&who, asset_id, new_my_asset_balance)?;
+        <TotalIssuedAssets<T>>::insert(asset_id, new_total_issued_asset);
+        Self::_record_supply(asset_id, new_total_issued_asset);
+        Self::_audit(asset_id);
+        // --------------------- 更新 --- ここまで
+
+        Ok(())
+    }
+
+    /// 資産送信の確認（内部処理）
+    /// _send_asset が更新前に行う確認。複数の送信をまとめて行う呼び出し側が、更新の前に使う
+    fn _ensure_can_send(from: &T::AccountId, to: &T::AccountId, asset_id: T::Hash, qty: u64) -> Result {
+        ensure!(!Self::frozen(asset_id), "Asset is frozen");
+        // 所有確認
+        // - 資産確認
+        Self::_ensure_registered_holder(from, asset_id)?;
+        // - 送信額確認
+        let my_asset_balance = Self::my_asset_balance((from.clone(), asset_id));
+        ensure!(my_asset_balance >= qty, "Your asset is less than you want to send the amount.");
+        ensure!(Self::spendable_balance(from, asset_id) >= qty, "Your asset is locked for voting");
+
+        // 自分自身への送信は残高を変えない
+        if from == to {
+            return Ok(());
+        }
+
+        // 受信者資産
+        let to_asset_balance = Self::my_asset_balance((to.clone(), asset_id));
+        to_asset_balance.checked_add(qty).ok_or("Overflow adding (to)'s asset")?;
+        // 保有者数の上限
+        let max_holders = Self::max_holders(asset_id);
+        if max_holders > 0 && to_asset_balance == 0 && qty > 0 {
+            // 全量送信なら送信者が保有者から外れるため、保有者数は変わらない
+            let leaving = if my_asset_balance == qty { 1 } else { 0 };
+            ensure!(Self::holder_count(asset_id) + 1 - leaving <= max_holders, "Max holders reached");
+        }
+        Ok(())
+    }
+
+    /// 資産送信（内部処理）
+    /// sendasset と、モジュール管理アカウントとの間の資産移動で共有する
+    fn _send_asset(from: T::AccountId, to: T::AccountId, asset_id: T::Hash, qty: u64) -> Result {
+        Self::_ensure_can_send(&from, &to, asset_id, qty)?;
+
+        // 自分自身への送信は残高を変えない
+        // (送信者と受信者の残高を別々に更新すると qty 分増えてしまう)
+        if from == to {
+            return Ok(());
+        }
+
+        // 送信者資産
+        let new_my_asset_balance = Self::my_asset_balance((from.clone(), asset_id)).checked_sub(qty)
+            .ok_or("Your asset is less than you want to send the amount.")?;
+        // 受信者資産
+        let new_to_asset_balance = Self::my_asset_balance((to.clone(), asset_id)).checked_add(qty)
+            .ok_or("Overflow adding (to)'s asset")?;
+        // 1 人の所有量が発行済量を超えることはない。超えた場合は集計のずれがある
+        debug_assert!(new_to_asset_balance <= Self::total_issued_asset(asset_id),
+            "holder balance exceeds the issued total");
+        // 累計移動量 (参考値のため、あふれる場合は最大値で止める)
+        let new_total_volume = Self::total_volume(asset_id).saturating_add(u128::from(qty));
+
+        // --------------------- 更新
+        // 受信者を先に更新する
+        // 受信者の新規登録で失敗した場合、送信者の資産はまだ変わっていない
+        Self::_set_balance(&to, asset_id, new_to_asset_balance)?;
+        Self::_set_balance(&from, asset_id, new_my_asset_balance)?;
+        <TotalVolume<T>>::insert(asset_id, new_total_volume);
+        // --------------------- 更新 --- ここまで
+        Self::_audit(asset_id);
+
+        Ok(())
+    }
+}
+
+impl<T: Trait> Fungible<T::AccountId, T::Hash> for Module<T> {
+    fn total_issuance(asset_id: T::Hash) -> u64 {
+        Self::total_issued_asset(asset_id)
+    }
+
+    fn free_balance(who: &T::AccountId, asset_id: T::Hash) -> u64 {
+        Self::my_asset_balance((who.clone(), asset_id))
+    }
+
+    fn transfer(from: &T::AccountId, to: &T::AccountId, asset_id: T::Hash, qty: u64) -> Result {
+        Self::_sendasset(from.clone(), to.clone(), asset_id, qty, false)
+    }
+
+    fn can_slash(who: &T::AccountId, asset_id: T::Hash, qty: u64) -> bool {
+        <MyAssetsIndex<T>>::exists((who.clone(), asset_id))
+            && Self::spendable_balance(who, asset_id) >= qty
+    }
+}
+
+/// テスト
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use runtime_io::with_externalities;
+    use primitives::{H256, Blake2Hasher};
+    use support::{impl_outer_origin, impl_outer_event, assert_ok, assert_noop};
+    use runtime_primitives::{
+        BuildStorage,
+        traits::{BlakeTwo256, IdentityLookup, OnInitialise, OnFinalise, Lazy},
+        testing::{Digest, DigestItem, Header}
+    };
+
+    impl_outer_origin! {
+        pub enum Origin for Test {}
+    }
+
+    mod ibchain {
+        pub use super::super::Event;
+    }
+
+    impl_outer_event! {
+        pub enum TestEvent for Test {
+            balances<T>, ibchain<T>,
+        }
+    }
+
+    #[derive(Clone, Eq, PartialEq)]
+    pub struct Test;
+    impl system::Trait for Test {
+        type Origin = Origin;
+        type Index = u64;
+        type BlockNumber = u64;
+        type Hash = H256;
+        type Hashing = BlakeTwo256;
+        type Digest = Digest;
+        type AccountId = u64;
+        type Lookup = IdentityLookup<u64>;
+        type Header = Header;
+        type Event = TestEvent;
+        type Log = DigestItem;
+    }
+    impl balances::Trait for Test {
+        type Balance = u64;
+        type OnFreeBalanceZero = ();
+        type OnNewAccount = ();
+        type EnsureAccountLiquid = ();
+        type Event = TestEvent;
+    }
+    impl Trait for Test {
+        type Event = TestEvent;
+        type Signature = TestSignature;
+    }
+
+    /// テスト用の署名 (署名者, 署名内容)
+    /// 署名者と内容が一致すれば正しい署名とする
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, Debug)]
+    pub struct TestSignature(u64, Vec<u8>);
+
+    impl Verify for TestSignature {
+        type Signer = u64;
+        fn verify<L: Lazy<[u8]>>(&self, mut msg: L, signer: &u64) -> bool {
+            self.0 == *signer && &self.1[..] == msg.get()
+        }
+    }
+    type IBChain = Module<Test>;
+    type System = system::Module<Test>;
+    type Balances = balances::Module<Test>;
+
+    /// テスト用のジェネシス
+    fn new_test_ext() -> runtime_io::TestExternalities<Blake2Hasher> {
+        ExtBuilder::default().build()
+    }
+
+    /// ジェネシス設定を変えたい場合に使う
+    struct ExtBuilder {
+        min_issue_qty: u64,
+        name_policy: NamePolicy,
+        genesis_assets: Vec<(u64, Vec<u8>, u64)>,
+        native_balances: Vec<(u64, u64)>,
+        vesting: Vec<(u64, u64, u64)>,
+    }
+
+    impl Default for ExtBuilder {
+        fn default() -> Self {
+            ExtBuilder {
+                min_issue_qty: 1,
+                name_policy: NamePolicy::Printable,
+                genesis_assets: vec![],
+                native_balances: vec![],
+                vesting: vec![],
+            }
+        }
+    }
+
+    impl ExtBuilder {
+        fn min_issue_qty(mut self, qty: u64) -> Self {
+            self.min_issue_qty = qty;
+            self
+        }
+
+        fn name_policy(mut self, policy: NamePolicy) -> Self {
+            self.name_policy = policy;
+            self
+        }
+
+        fn genesis_asset(mut self, owner: u64, name: &[u8], qty: u64) -> Self {
+            self.genesis_assets.push((owner, name.to_vec(), qty));
+            self
+        }
+
+        fn native_balance(mut self, who: u64, balance: u64) -> Self {
+            self.native_balances.push((who, balance));
+            self
+        }
+
+        /// ネイティブトークンの権利確定 (アカウント, 開始ブロック, 期間)
+        fn vesting(mut self, who: u64, begin: u64, length: u64) -> Self {
+            self.vesting.push((who, begin, length));
+            self
+        }
+
+        fn build(self) -> runtime_io::TestExternalities<Blake2Hasher> {
+            let mut t = system::GenesisConfig::<Test>::default().build_storage().unwrap().0;
+            t.extend(balances::GenesisConfig::<Test> {
+                balances: self.native_balances,
+                existential_deposit: 0,
+                transfer_fee: 0,
+                creation_fee: 0,
+                vesting: self.vesting,
+            }.build_storage().unwrap().0);
+            t.extend(GenesisConfig::<Test> {
+                min_issue_qty: self.min_issue_qty,
+                name_policy: self.name_policy,
+                event_verbosity: EVENTS_DETAILED,
+                genesis_assets: self.genesis_assets,
+            }.build_storage().unwrap().0);
+            t.into()
+        }
+    }
+
+    /// これまでに発行された ibchain のイベント
+    fn ibchain_events() -> Vec<RawEvent<u64, H256, u64>> {
+        System::events()
+            .into_iter()
+            .filter_map(|record| match record.event {
+                TestEvent::ibchain(event) => Some(event),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// テスト用のマークル節
+    fn merkle_node(a: H256, b: H256) -> H256 {
+        if a.as_ref() <= b.as_ref() {
+            BlakeTwo256::hash_of(&(a, b))
+        } else {
+            BlakeTwo256::hash_of(&(b, a))
+        }
+    }
+
+    /// 直近に作成された承諾待ち送信の ID
+    fn last_pending_transfer() -> H256 {
+        ibchain_events().into_iter().filter_map(|event| match event {
+            RawEvent::TransferProposed(transfer_id, ..) => Some(transfer_id),
+            _ => None,
+        }).last().unwrap()
+    }
+
+    /// 直近に作成された委員会操作の提案 ID
+    fn last_admin_proposal() -> H256 {
+        ibchain_events().into_iter().filter_map(|event| match event {
+            RawEvent::AdminActionProposed(proposal_id, ..) => Some(proposal_id),
+            _ => None,
+        }).last().unwrap()
+    }
+
+    /// 直近に発行した資産の ID
+    fn last_issued_asset(who: u64) -> H256 {
+        let count = IBChain::owned_asset_count(&who);
+        IBChain::asset_of_owner_by_index((who, count - 1))
+    }
+
+    #[test]
+    fn issue_populates_all_storage() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+
+            let asset = IBChain::asset(asset_id);
+            assert_eq!(asset.id, asset_id);
+            assert_eq!(asset.name, b"coin".to_vec());
+            assert!(asset.open);
+            assert_eq!(IBChain::owner_of(asset_id), Some(1));
+
+            assert_eq!(IBChain::asset_by_index(0), asset_id);
+            assert_eq!(IBChain::all_asset_count(), 1);
+            assert_eq!(<AllAssetsIndex<Test>>::get(asset_id), 0);
+
+            assert_eq!(IBChain::asset_of_owner_by_index((1, 0)), asset_id);
+            assert_eq!(IBChain::owned_asset_count(&1), 1);
+            assert_eq!(<OwnedAssetsIndex<Test>>::get(asset_id), 0);
+
+            assert_eq!(IBChain::total_issued_asset(asset_id), 100);
+            assert_eq!(<Nonce<Test>>::get(), 1);
+
+            assert_eq!(IBChain::my_asset_by_index((1, 0)), asset_id);
+            assert_eq!(IBChain::my_asset_count(&1), 1);
+            assert_eq!(<MyAssetsIndex<Test>>::get((1, asset_id)), 0);
+            assert_eq!(IBChain::my_asset_balance((1, asset_id)), 100);
+        });
+    }
+
+    #[test]
+    fn second_issue_appends_to_indexes() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"first".to_vec(), 100, true));
+            let first = last_issued_asset(1);
+            assert_ok!(IBChain::issue(Origin::signed(1), b"second".to_vec(), 5, false));
+            let second = last_issued_asset(1);
+
+            assert!(first != second);
+            assert_eq!(IBChain::all_asset_count(), 2);
+            assert_eq!(IBChain::asset_by_index(1), second);
+            assert_eq!(<AllAssetsIndex<Test>>::get(second), 1);
+            assert_eq!(IBChain::owned_asset_count(&1), 2);
+            assert_eq!(<OwnedAssetsIndex<Test>>::get(second), 1);
+            assert_eq!(IBChain::my_asset_count(&1), 2);
+            assert_eq!(<MyAssetsIndex<Test>>::get((1, second)), 1);
+            assert!(!IBChain::asset(second).open);
+        });
+    }
+
+    #[test]
+    fn issuemore_increases_total_and_owner_balance() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+
+            assert_ok!(IBChain::issuemore(Origin::signed(1), asset_id, 50));
+
+            assert_eq!(IBChain::total_issued_asset(asset_id), 150);
+            assert_eq!(IBChain::my_asset_balance((1, asset_id)), 150);
+        });
+    }
+
+    #[test]
+    fn issuemore_rejects_closed_asset_and_non_owner() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"open".to_vec(), 100, true));
+            let open_asset = last_issued_asset(1);
+            assert_ok!(IBChain::issue(Origin::signed(1), b"closed".to_vec(), 100, false));
+            let closed_asset = last_issued_asset(1);
+
+            assert_noop!(IBChain::issuemore(Origin::signed(1), closed_asset, 50), "You can not issue more");
+            assert_noop!(IBChain::issuemore(Origin::signed(2), open_asset, 50), "You do not own this asset");
+            assert_noop!(IBChain::issuemore(Origin::signed(1), H256::zero(), 50), "This asset does not exist");
+        });
+    }
+
+    #[test]
+    fn sendasset_to_new_holder_creates_entries() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+
+            assert_ok!(IBChain::sendasset(Origin::signed(1), 2, asset_id, 30));
+
+            assert_eq!(IBChain::my_asset_by_index((2, 0)), asset_id);
+            assert_eq!(IBChain::my_asset_count(&2), 1);
+            assert_eq!(<MyAssetsIndex<Test>>::get((2, asset_id)), 0);
+            assert_eq!(IBChain::my_asset_balance((2, asset_id)), 30);
+            assert_eq!(IBChain::my_asset_balance((1, asset_id)), 70);
+            assert_eq!(IBChain::total_issued_asset(asset_id), 100);
+        });
+    }
+
+    #[test]
+    fn sendasset_to_existing_holder_only_moves_balance() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+            assert_ok!(IBChain::sendasset(Origin::signed(1), 2, asset_id, 30));
+
+            assert_ok!(IBChain::sendasset(Origin::signed(1), 2, asset_id, 20));
+
+            assert_eq!(IBChain::my_asset_count(&2), 1);
+            assert_eq!(IBChain::my_asset_balance((2, asset_id)), 50);
+            assert_eq!(IBChain::my_asset_balance((1, asset_id)), 50);
+        });
+    }
+
+    #[test]
+    fn sendasset_rejects_unheld_asset_and_overdraw() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+
+            assert_noop!(IBChain::sendasset(Origin::signed(2), 3, asset_id, 1), "This asset does not exist");
+            assert_noop!(
+                IBChain::sendasset(Origin::signed(1), 2, asset_id, 101),
+                "Your asset is less than you want to send the amount."
+            );
+        });
+    }
+
+    #[test]
+    fn issue_rejects_quantity_below_minimum() {
+        with_externalities(&mut ExtBuilder::default().min_issue_qty(100).build(), || {
+            assert_noop!(
+                IBChain::issue(Origin::signed(1), b"coin".to_vec(), 50, true),
+                "Issue quantity is below the minimum"
+            );
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            assert_eq!(IBChain::total_issued_asset(last_issued_asset(1)), 100);
+        });
+    }
+
+    #[test]
+    fn issue_rejects_zero_quantity_by_default() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_eq!(IBChain::min_issue_qty(), 1);
+            assert_noop!(
+                IBChain::issue(Origin::signed(1), b"coin".to_vec(), 0, true),
+                "Issue quantity is below the minimum"
+            );
+        });
+    }
+
+    #[test]
+    fn lock_for_bridge_debits_caller() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+            let bridge = IBChain::bridge_account();
+
+            assert_ok!(IBChain::lock_for_bridge(Origin::signed(1), asset_id, 30, b"0xdead".to_vec()));
+
+            assert_eq!(IBChain::my_asset_balance((1, asset_id)), 70);
+            assert_eq!(IBChain::my_asset_balance((bridge, asset_id)), 30);
+            assert_eq!(IBChain::total_issued_asset(asset_id), 100);
+        });
+    }
+
+    #[test]
+    fn lock_for_bridge_rejects_more_than_balance() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+
+            assert_noop!(
+                IBChain::lock_for_bridge(Origin::signed(1), asset_id, 101, b"0xdead".to_vec()),
+                "Your asset is less than you want to send the amount."
+            );
+        });
+    }
+
+    /// 最後に作られたブリッジロックの ID
+    fn last_bridge_lock() -> H256 {
+        ibchain_events().into_iter().filter_map(|event| match event {
+            RawEvent::BridgeLocked(_, lock_id, _, _) => Some(lock_id),
+            _ => None,
+        }).last().unwrap()
+    }
+
+    #[test]
+    fn release_from_bridge_only_by_authority() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+            assert_ok!(IBChain::lock_for_bridge(Origin::signed(1), asset_id, 30, b"0xdead".to_vec()));
+            let lock_id = last_bridge_lock();
+
+            assert_noop!(
+                IBChain::release_from_bridge(Origin::signed(9), lock_id, 2, 10),
+                "No bridge authority"
+            );
+            assert_ok!(IBChain::set_bridge_authority(Origin::ROOT, 9));
+            assert_noop!(
+                IBChain::release_from_bridge(Origin::signed(1), lock_id, 2, 10),
+                "You are not the bridge authority"
+            );
+
+            assert_ok!(IBChain::release_from_bridge(Origin::signed(9), lock_id, 2, 10));
+            assert_eq!(IBChain::my_asset_balance((2, asset_id)), 10);
+            assert_eq!(IBChain::my_asset_balance((IBChain::bridge_account(), asset_id)), 20);
+        });
+    }
+
+    #[test]
+    fn release_from_bridge_draws_down_the_lock() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+            assert_ok!(IBChain::set_bridge_authority(Origin::ROOT, 9));
+            assert_ok!(IBChain::lock_for_bridge(Origin::signed(1), asset_id, 30, b"0xdead".to_vec()));
+            let first = last_bridge_lock();
+            assert_ok!(IBChain::lock_for_bridge(Origin::signed(1), asset_id, 20, b"0xbeef".to_vec()));
+            let second = last_bridge_lock();
+
+            assert_noop!(IBChain::release_from_bridge(Origin::signed(9), H256::zero(), 2, 10),
+                "This bridge lock does not exist");
+            // ブリッジアカウントには 50 あるが、ロックごとの残量を超えては解放できない
+            assert_noop!(IBChain::release_from_bridge(Origin::signed(9), second, 2, 21),
+                "Release exceeds the bridge lock");
+
+            assert_ok!(IBChain::release_from_bridge(Origin::signed(9), first, 2, 10));
+            assert_eq!(IBChain::bridge_lock(first).unwrap().qty, 20);
+            assert_ok!(IBChain::release_from_bridge(Origin::signed(9), first, 2, 20));
+            assert!(IBChain::bridge_lock(first).is_none());
+            assert_noop!(IBChain::release_from_bridge(Origin::signed(9), first, 2, 1),
+                "This bridge lock does not exist");
+            assert_eq!(IBChain::bridge_lock(second).unwrap().qty, 20);
+            assert_eq!(IBChain::my_asset_balance((2, asset_id)), 30);
+            assert_eq!(IBChain::my_asset_balance((IBChain::bridge_account(), asset_id)), 20);
+        });
+    }
+
+    #[test]
+    fn mintable_assets_lists_open_owned_assets() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"open".to_vec(), 100, true));
+            let open_asset = last_issued_asset(1);
+            assert_ok!(IBChain::issue(Origin::signed(1), b"closed".to_vec(), 100, false));
+            assert_ok!(IBChain::issue(Origin::signed(2), b"other".to_vec(), 100, true));
+
+            assert_eq!(IBChain::mintable_assets(&1), vec![open_asset]);
+            assert_eq!(IBChain::mintable_assets(&3), Vec::<H256>::new());
+        });
+    }
+
+    #[test]
+    fn owner_sends_full_quantity() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+
+            assert_ok!(IBChain::sendasset(Origin::signed(1), 2, asset_id, 40));
+
+            assert_eq!(IBChain::my_asset_balance((1, asset_id)), 60);
+            assert_eq!(IBChain::my_asset_balance((2, asset_id)), 40);
+        });
+    }
+
+    #[test]
+    fn send_to_owner_delivers_full_quantity() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+            assert_ok!(IBChain::sendasset(Origin::signed(1), 2, asset_id, 40));
+
+            assert_ok!(IBChain::sendasset(Origin::signed(2), 1, asset_id, 15));
+
+            assert_eq!(IBChain::my_asset_balance((1, asset_id)), 75);
+            assert_eq!(IBChain::my_asset_balance((2, asset_id)), 25);
+        });
+    }
+
+    #[test]
+    fn send_to_self_leaves_balance_unchanged() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+
+            assert_ok!(IBChain::sendasset(Origin::signed(1), 1, asset_id, 40));
+
+            assert_eq!(IBChain::my_asset_balance((1, asset_id)), 100);
+            assert_eq!(IBChain::total_issued_asset(asset_id), 100);
+            assert_noop!(
+                IBChain::sendasset(Origin::signed(1), 1, asset_id, 101),
+                "Your asset is less than you want to send the amount."
+            );
+        });
+    }
+
+    #[test]
+    fn burn_reduces_balance_and_total() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+
+            assert_ok!(IBChain::burn(Origin::signed(1), asset_id, 30));
+
+            assert_eq!(IBChain::my_asset_balance((1, asset_id)), 70);
+            assert_eq!(IBChain::total_issued_asset(asset_id), 70);
+        });
+    }
+
+    #[test]
+    fn burn_from_with_sufficient_allowance() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+            assert_ok!(IBChain::approve(Origin::signed(1), 2, asset_id, 50));
+
+            assert_ok!(IBChain::burn_from(Origin::signed(2), 1, asset_id, 30));
+
+            assert_eq!(IBChain::my_asset_balance((1, asset_id)), 70);
+            assert_eq!(IBChain::total_issued_asset(asset_id), 70);
+            assert_eq!(IBChain::allowance((1, 2, asset_id)), 20);
+        });
+    }
+
+    #[test]
+    fn burn_from_rejects_insufficient_allowance() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+            assert_ok!(IBChain::approve(Origin::signed(1), 2, asset_id, 20));
+
+            assert_noop!(
+                IBChain::burn_from(Origin::signed(2), 1, asset_id, 30),
+                "Allowance is less than you want to burn"
+            );
+            assert_noop!(
+                IBChain::burn_from(Origin::signed(3), 1, asset_id, 1),
+                "Allowance is less than you want to burn"
+            );
+        });
+    }
+
+    #[test]
+    fn burn_from_rejects_insufficient_balance() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+            assert_ok!(IBChain::approve(Origin::signed(1), 2, asset_id, 500));
+
+            assert_noop!(
+                IBChain::burn_from(Origin::signed(2), 1, asset_id, 101),
+                "Your asset is less than you want to burn"
+            );
+        });
+    }
+
+    #[test]
+    fn send_asset_batch_sends_to_each_recipient() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+
+            assert_ok!(IBChain::send_asset_batch(Origin::signed(1), asset_id, vec![(2, 10), (3, 20)], false));
+
+            assert_eq!(IBChain::my_asset_balance((1, asset_id)), 70);
+            assert_eq!(IBChain::my_asset_balance((2, asset_id)), 10);
+            assert_eq!(IBChain::my_asset_balance((3, asset_id)), 20);
+        });
+    }
+
+    #[test]
+    fn send_asset_batch_rejects_duplicate_recipient() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+
+            assert_noop!(
+                IBChain::send_asset_batch(Origin::signed(1), asset_id, vec![(2, 10), (3, 20), (2, 5)], false),
+                "Duplicate recipient in batch"
+            );
+        });
+    }
+
+    #[test]
+    fn send_asset_batch_checks_total_before_sending() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+
+            assert_noop!(
+                IBChain::send_asset_batch(Origin::signed(1), asset_id, vec![(2, 60), (3, 60)], false),
+                "Your asset is less than you want to send the amount."
+            );
+        });
+    }
+
+    #[test]
+    fn supply_history_records_mints_and_burns_in_order() {
+        with_externalities(&mut new_test_ext(), || {
+            System::set_block_number(1);
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+            System::set_block_number(2);
+            assert_ok!(IBChain::issuemore(Origin::signed(1), asset_id, 50));
+            System::set_block_number(3);
+            assert_ok!(IBChain::burn(Origin::signed(1), asset_id, 30));
+            // 同じブロック内の変更は 1 件にまとめる
+            assert_ok!(IBChain::burn(Origin::signed(1), asset_id, 20));
+
+            assert_eq!(IBChain::supply_history(asset_id), vec![(1, 100), (2, 150), (3, 100)]);
+        });
+    }
+
+    #[test]
+    fn supply_history_is_capped() {
+        with_externalities(&mut new_test_ext(), || {
+            System::set_block_number(1);
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 1, true));
+            let asset_id = last_issued_asset(1);
+            for n in 2..(MAX_SUPPLY_CHECKPOINTS as u64 + 10) {
+                System::set_block_number(n);
+                assert_ok!(IBChain::issuemore(Origin::signed(1), asset_id, 1));
+            }
+
+            let history = IBChain::supply_history(asset_id);
+            assert_eq!(history.len(), MAX_SUPPLY_CHECKPOINTS);
+            assert_eq!(history.first(), Some(&(10, 10)));
+            assert_eq!(history.last(), Some(&(MAX_SUPPLY_CHECKPOINTS as u64 + 9, MAX_SUPPLY_CHECKPOINTS as u64 + 9)));
+        });
+    }
+
+    #[test]
+    fn account_overview_lists_held_and_issued_assets() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"mine".to_vec(), 100, true));
+            let mine = last_issued_asset(1);
+            assert_ok!(IBChain::issue(Origin::signed(2), b"theirs".to_vec(), 100, true));
+            let theirs = last_issued_asset(2);
+            assert_ok!(IBChain::sendasset(Origin::signed(2), 1, theirs, 10));
+            assert_ok!(IBChain::sendasset(Origin::signed(1), 2, mine, 25));
+
+            assert_eq!(IBChain::account_overview(&1), AccountOverview {
+                held: vec![(mine, 75), (theirs, 10)],
+                held_truncated: false,
+                issued: vec![mine],
+                issued_truncated: false,
+            });
+            assert_eq!(IBChain::account_overview(&3), AccountOverview::default());
+        });
+    }
+
+    #[test]
+    fn holder_count_tracks_zero_crossings() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+            assert_eq!(IBChain::holder_count(asset_id), 1);
+
+            assert_ok!(IBChain::sendasset(Origin::signed(1), 2, asset_id, 40));
+            assert_eq!(IBChain::holder_count(asset_id), 2);
+
+            // 既存の保有者への送信では変わらない
+            assert_ok!(IBChain::sendasset(Origin::signed(1), 2, asset_id, 10));
+            assert_eq!(IBChain::holder_count(asset_id), 2);
+
+            // 全量送信で 1 減り、受信者が新規なら 1 増える
+            assert_ok!(IBChain::sendasset(Origin::signed(2), 3, asset_id, 50));
+            assert_eq!(IBChain::holder_count(asset_id), 2);
+
+            assert_ok!(IBChain::burn(Origin::signed(3), asset_id, 50));
+            assert_eq!(IBChain::holder_count(asset_id), 1);
+        });
+    }
+
+    #[test]
+    fn holder_count_matches_positive_balances_under_random_operations() {
+        with_externalities(&mut new_test_ext(), || {
+            let accounts = [1u64, 2, 3, 4];
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 1000, true));
+            let asset_id = last_issued_asset(1);
+
+            // 決定的な擬似乱数 (xorshift)
+            let mut seed: u64 = 0x2545_f491_4f6c_dd1d;
+            let mut next = move |n: u64| {
+                seed ^= seed << 13;
+                seed ^= seed >> 7;
+                seed ^= seed << 17;
+                seed % n
+            };
+
+            for _ in 0..500 {
+                let who = accounts[next(4) as usize];
+                let balance = IBChain::my_asset_balance((who, asset_id));
+                let qty = if balance == 0 { 0 } else { next(balance + 1) };
+                match next(3) {
+                    0 => {
+                        let to = accounts[next(4) as usize];
+                        let _ = IBChain::sendasset(Origin::signed(who), to, asset_id, qty);
+                    },
+                    1 => {
+                        let _ = IBChain::burn(Origin::signed(who), asset_id, qty);
+                    },
+                    _ => {
+                        assert_ok!(IBChain::issuemore(Origin::signed(1), asset_id, next(50)));
+                    },
+                }
+
+                let holders = accounts.iter()
+                    .filter(|a| IBChain::my_asset_balance((**a, asset_id)) > 0)
+                    .count() as u64;
+                assert_eq!(IBChain::holder_count(asset_id), holders);
+            }
+        });
+    }
+
+    #[test]
+    fn sendasset_splits_community_cut() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 1000, true));
+            let asset_id = last_issued_asset(1);
+            assert_ok!(IBChain::set_community_fund(Origin::signed(1), asset_id, Some(9), 250));
+
+            assert_ok!(IBChain::sendasset(Origin::signed(1), 2, asset_id, 200));
+
+            assert_eq!(IBChain::my_asset_balance((1, asset_id)), 800);
+            assert_eq!(IBChain::my_asset_balance((2, asset_id)), 195);
+            assert_eq!(IBChain::my_asset_balance((9, asset_id)), 5);
+            assert_eq!(IBChain::total_issued_asset(asset_id), 1000);
+        });
+    }
+
+    #[test]
+    fn sendasset_without_community_fund_is_unsplit() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 1000, true));
+            let asset_id = last_issued_asset(1);
+            assert_ok!(IBChain::set_community_fund(Origin::signed(1), asset_id, None, 250));
+
+            assert_ok!(IBChain::sendasset(Origin::signed(1), 2, asset_id, 200));
+
+            assert_eq!(IBChain::my_asset_balance((2, asset_id)), 200);
+            assert_eq!(IBChain::community_fund(asset_id), None);
+        });
+    }
+
+    #[test]
+    fn community_share_cannot_exceed_quantity() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 1000, true));
+            let asset_id = last_issued_asset(1);
+
+            assert_noop!(
+                IBChain::set_community_fund(Origin::signed(1), asset_id, Some(9), 10_001),
+                "Community share exceeds 100%"
+            );
+            assert_noop!(
+                IBChain::set_community_fund(Origin::signed(2), asset_id, Some(9), 100),
+                "You do not own this asset"
+            );
+
+            assert_ok!(IBChain::set_community_fund(Origin::signed(1), asset_id, Some(9), 10_000));
+            assert_ok!(IBChain::sendasset(Origin::signed(1), 2, asset_id, 100));
+            assert_eq!(IBChain::my_asset_balance((2, asset_id)), 0);
+            assert_eq!(IBChain::my_asset_balance((9, asset_id)), 100);
+        });
+    }
+
+    #[test]
+    fn ascii_only_policy() {
+        let policy = NamePolicy::AsciiOnly;
+        assert!(policy.allows(b"Gold Coin #1"));
+        assert!(!policy.allows("金貨".as_bytes()));
+        assert!(!policy.allows(b"coin\n"));
+        assert!(!policy.allows(&[0xff, 0xfe]));
+    }
+
+    #[test]
+    fn utf8_policy() {
+        let policy = NamePolicy::Utf8;
+        assert!(policy.allows(b"Gold Coin"));
+        assert!(policy.allows("金貨".as_bytes()));
+        assert!(policy.allows(b"coin\n"));
+        assert!(!policy.allows(&[0xff, 0xfe]));
+    }
+
+    #[test]
+    fn printable_policy() {
+        let policy = NamePolicy::Printable;
+        assert!(policy.allows(b"Gold Coin"));
+        assert!(policy.allows("金貨".as_bytes()));
+        assert!(!policy.allows(b"coin\n"));
+        assert!(!policy.allows(b"\x1b[31mcoin"));
+        assert!(!policy.allows(&[0xff, 0xfe]));
+    }
+
+    #[test]
+    fn issue_enforces_name_policy() {
+        with_externalities(&mut ExtBuilder::default().name_policy(NamePolicy::AsciiOnly).build(), || {
+            assert_noop!(
+                IBChain::issue(Origin::signed(1), "金貨".as_bytes().to_vec(), 100, true),
+                "Asset name contains disallowed characters"
+            );
+            assert_ok!(IBChain::issue(Origin::signed(1), b"gold".to_vec(), 100, true));
+
+            assert!(IBChain::set_name_policy(Origin::signed(1), NamePolicy::Utf8).is_err());
+            assert_ok!(IBChain::set_name_policy(Origin::ROOT, NamePolicy::Utf8));
+            assert_ok!(IBChain::issue(Origin::signed(1), "金貨".as_bytes().to_vec(), 100, true));
+        });
+    }
+
+    #[test]
+    fn event_seq_increments_once_per_emitting_operation() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_eq!(IBChain::event_seq(), 0);
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            assert_eq!(IBChain::event_seq(), 1);
+            let asset_id = last_issued_asset(1);
+
+            assert_ok!(IBChain::issuemore(Origin::signed(1), asset_id, 10));
+            assert_eq!(IBChain::event_seq(), 2);
+            assert_ok!(IBChain::sendasset(Origin::signed(1), 2, asset_id, 10));
+            assert_eq!(IBChain::event_seq(), 3);
+            assert_ok!(IBChain::burn(Origin::signed(2), asset_id, 5));
+            assert_eq!(IBChain::event_seq(), 4);
+
+            // 一括送信は送信先ごとに 1 つ
+            assert_ok!(IBChain::send_asset_batch(Origin::signed(1), asset_id, vec![(3, 1), (4, 1)], false));
+            assert_eq!(IBChain::event_seq(), 6);
+
+            // 失敗した操作では進まない
+            assert!(IBChain::sendasset(Origin::signed(1), 2, asset_id, 1000).is_err());
+            assert_eq!(IBChain::event_seq(), 6);
+
+            // 対象外のイベントでは進まない
+            assert_ok!(IBChain::approve(Origin::signed(1), 2, asset_id, 10));
+            assert_eq!(IBChain::event_seq(), 6);
+        });
+    }
+
+    #[test]
+    fn vote_locked_amount_cannot_be_sent_or_burned() {
+        with_externalities(&mut new_test_ext(), || {
+            System::set_block_number(1);
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+
+            assert_ok!(IBChain::lock_for_vote(Origin::signed(1), asset_id, 60, 10));
+            assert_eq!(IBChain::spendable_balance(&1, asset_id), 40);
+
+            assert_noop!(IBChain::sendasset(Origin::signed(1), 2, asset_id, 41), "Your asset is locked for voting");
+            assert_noop!(IBChain::burn(Origin::signed(1), asset_id, 41), "Your asset is locked for voting");
+            assert_ok!(IBChain::sendasset(Origin::signed(1), 2, asset_id, 40));
+            assert_eq!(IBChain::spendable_balance(&1, asset_id), 0);
+        });
+    }
+
+    #[test]
+    fn vote_lock_unlocks_after_deadline() {
+        with_externalities(&mut new_test_ext(), || {
+            System::set_block_number(1);
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+            assert_ok!(IBChain::lock_for_vote(Origin::signed(1), asset_id, 60, 10));
+
+            System::set_block_number(9);
+            assert_noop!(IBChain::unlock_vote(Origin::signed(1), asset_id), "Vote lock has not expired");
+
+            System::set_block_number(10);
+            assert_eq!(IBChain::spendable_balance(&1, asset_id), 100);
+            assert_ok!(IBChain::unlock_vote(Origin::signed(1), asset_id));
+            assert!(!<VoteLocks<Test>>::exists((1, asset_id)));
+            assert_ok!(IBChain::sendasset(Origin::signed(1), 2, asset_id, 100));
+        });
+    }
+
+    #[test]
+    fn vote_lock_can_only_be_extended() {
+        with_externalities(&mut new_test_ext(), || {
+            System::set_block_number(1);
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+            assert_ok!(IBChain::lock_for_vote(Origin::signed(1), asset_id, 60, 10));
+
+            assert_noop!(IBChain::lock_for_vote(Origin::signed(1), asset_id, 50, 10), "Vote lock can only be extended");
+            assert_noop!(IBChain::lock_for_vote(Origin::signed(1), asset_id, 60, 9), "Vote lock can only be extended");
+            assert_noop!(IBChain::lock_for_vote(Origin::signed(1), asset_id, 101, 20), "Your asset is less than you want to lock");
+            assert_ok!(IBChain::lock_for_vote(Origin::signed(1), asset_id, 80, 20));
+            assert_eq!(IBChain::vote_lock((1, asset_id)), (80, 20));
+        });
+    }
+
+    #[test]
+    fn fungible_matches_storage() {
+        with_externalities(&mut new_test_ext(), || {
+            System::set_block_number(1);
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+
+            assert_eq!(<IBChain as Fungible<u64, H256>>::total_issuance(asset_id), IBChain::total_issued_asset(asset_id));
+            assert_eq!(<IBChain as Fungible<u64, H256>>::free_balance(&1, asset_id), IBChain::my_asset_balance((1, asset_id)));
+
+            assert_ok!(<IBChain as Fungible<u64, H256>>::transfer(&1, &2, asset_id, 30));
+            assert_eq!(<IBChain as Fungible<u64, H256>>::free_balance(&1, asset_id), 70);
+            assert_eq!(<IBChain as Fungible<u64, H256>>::free_balance(&2, asset_id), IBChain::my_asset_balance((2, asset_id)));
+            assert_eq!(IBChain::my_asset_balance((2, asset_id)), 30);
+            assert_eq!(<IBChain as Fungible<u64, H256>>::total_issuance(asset_id), 100);
+        });
+    }
+
+    #[test]
+    fn fungible_can_slash_respects_balance_and_locks() {
+        with_externalities(&mut new_test_ext(), || {
+            System::set_block_number(1);
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+
+            assert!(<IBChain as Fungible<u64, H256>>::can_slash(&1, asset_id, 100));
+            assert!(!<IBChain as Fungible<u64, H256>>::can_slash(&1, asset_id, 101));
+            assert!(!<IBChain as Fungible<u64, H256>>::can_slash(&2, asset_id, 0));
+
+            assert_ok!(IBChain::lock_for_vote(Origin::signed(1), asset_id, 60, 10));
+            assert!(<IBChain as Fungible<u64, H256>>::can_slash(&1, asset_id, 40));
+            assert!(!<IBChain as Fungible<u64, H256>>::can_slash(&1, asset_id, 41));
+        });
+    }
+
+    #[test]
+    fn issue_collection_issues_and_indexes_items() {
+        with_externalities(&mut new_test_ext(), || {
+            let items = vec![
+                (b"sword".to_vec(), 1),
+                (b"shield".to_vec(), 2),
+                (b"helmet".to_vec(), 3),
+            ];
+            assert_ok!(IBChain::issue_collection(Origin::signed(1), b"armoury".to_vec(), items));
+
+            let assets = IBChain::collection_assets(b"armoury".to_vec());
+            assert_eq!(assets.len(), 3);
+            assert_eq!(IBChain::owned_assets(&1), assets);
+            for (asset_id, qty) in assets.iter().zip(1..) {
+                assert_eq!(IBChain::collection_of(asset_id), b"armoury".to_vec());
+                assert_eq!(IBChain::my_asset_balance((1, *asset_id)), qty);
+                assert!(!IBChain::asset(asset_id).open);
+            }
+            assert_eq!(IBChain::asset(assets[1]).name, b"shield".to_vec());
+
+            assert_noop!(
+                IBChain::issue_collection(Origin::signed(1), b"armoury".to_vec(), vec![(b"boots".to_vec(), 1)]),
+                "Collection already exists"
+            );
+        });
+    }
+
+    #[test]
+    fn issue_collection_checks_every_item_first() {
+        with_externalities(&mut new_test_ext(), || {
+            let items = vec![
+                (b"sword".to_vec(), 1),
+                (b"shield".to_vec(), 0),
+            ];
+            assert_noop!(
+                IBChain::issue_collection(Origin::signed(1), b"armoury".to_vec(), items),
+                "Issue quantity is below the minimum"
+            );
+            assert_eq!(IBChain::all_asset_count(), 0);
+        });
+    }
+
+    #[test]
+    fn issuemore_reports_total_and_balance_overflow_separately() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+
+            // 発行者の所有量だけが上限近く
+            <MyAssetBalances<Test>>::insert((1, asset_id), u64::max_value() - 1);
+            assert_noop!(
+                IBChain::issuemore(Origin::signed(1), asset_id, 10),
+                "Overflow adding a new Asset to the owner's balance"
+            );
+
+            // 発行済量が上限近く
+            <MyAssetBalances<Test>>::insert((1, asset_id), 100);
+            <TotalIssuedAssets<Test>>::insert(asset_id, u64::max_value() - 1);
+            assert_noop!(
+                IBChain::issuemore(Origin::signed(1), asset_id, 10),
+                "Overflow adding a new Asset"
+            );
+        });
+    }
+
+    #[test]
+    fn issuance_policy_reflects_configuration() {
+        with_externalities(&mut ExtBuilder::default().min_issue_qty(50).name_policy(NamePolicy::AsciiOnly).build(), || {
+            assert_eq!(IBChain::issuance_policy(), IssuancePolicy {
+                min_issue_qty: 50,
+                name_policy: NamePolicy::AsciiOnly,
+                max_collection_items: MAX_BATCH_LEN as u32,
+            });
+
+            assert_ok!(IBChain::set_min_issue_qty(Origin::ROOT, 7));
+            assert_eq!(IBChain::issuance_policy().min_issue_qty, 7);
+        });
+    }
+
+    #[test]
+    fn wrap_locks_base_and_issues_wrapped() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let base = last_issued_asset(1);
+
+            assert_ok!(IBChain::wrap(Origin::signed(1), base, 30, b"wcoin".to_vec()));
+            let wrapped = last_issued_asset(1);
+
+            assert_eq!(IBChain::my_asset_balance((1, base)), 70);
+            assert_eq!(IBChain::my_asset_balance((IBChain::wrap_account(), base)), 30);
+            assert_eq!(IBChain::my_asset_balance((1, wrapped)), 30);
+            assert_eq!(IBChain::total_issued_asset(wrapped), 30);
+            assert_eq!(IBChain::wrapped_backing(wrapped), Some((base, 30)));
+            assert!(!IBChain::asset(wrapped).open);
+
+            assert_noop!(
+                IBChain::wrap(Origin::signed(1), base, 71, b"wcoin".to_vec()),
+                "Your asset is less than you want to send the amount."
+            );
+            assert_noop!(
+                IBChain::wrap(Origin::signed(1), wrapped, 10, b"wwcoin".to_vec()),
+                "Wrapped assets can not be wrapped again"
+            );
+        });
+    }
+
+    #[test]
+    fn unwrap_burns_wrapped_and_returns_base() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let base = last_issued_asset(1);
+            assert_ok!(IBChain::wrap(Origin::signed(1), base, 30, b"wcoin".to_vec()));
+            let wrapped = last_issued_asset(1);
+
+            // ラップ資産を受け取った人もアンラップできる
+            assert_ok!(IBChain::sendasset(Origin::signed(1), 2, wrapped, 10));
+            assert_ok!(IBChain::unwrap(Origin::signed(2), wrapped, 10));
+            assert_eq!(IBChain::my_asset_balance((2, base)), 10);
+            assert_eq!(IBChain::my_asset_balance((2, wrapped)), 0);
+
+            assert_ok!(IBChain::unwrap(Origin::signed(1), wrapped, 5));
+            assert_eq!(IBChain::my_asset_balance((1, base)), 75);
+            assert_eq!(IBChain::my_asset_balance((IBChain::wrap_account(), base)), 15);
+            assert_eq!(IBChain::total_issued_asset(wrapped), 15);
+            assert_eq!(IBChain::wrapped_backing(wrapped), Some((base, 15)));
+        });
+    }
+
+    #[test]
+    fn unwrap_rejects_more_than_backing() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let base = last_issued_asset(1);
+            assert_ok!(IBChain::wrap(Origin::signed(1), base, 30, b"wcoin".to_vec()));
+            let wrapped = last_issued_asset(1);
+
+            assert_noop!(IBChain::unwrap(Origin::signed(1), wrapped, 31), "Unwrap exceeds backing");
+            assert_noop!(IBChain::unwrap(Origin::signed(1), base, 1), "This asset is not a wrapped asset");
+        });
+    }
+
+    #[test]
+    fn issue_fails_cleanly_at_counter_ceilings() {
+        with_externalities(&mut new_test_ext(), || {
+            <AllAssetsCount<Test>>::put(u64::max_value());
+            assert_noop!(
+                IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true),
+                "Overflow adding a new Asset to total supply"
+            );
+            <AllAssetsCount<Test>>::put(0);
+
+            <OwnedAssetsCount<Test>>::insert(1, u64::max_value());
+            assert_noop!(
+                IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true),
+                "Overflow adding a new Asset to account balance"
+            );
+            // 他のアカウントは発行できる
+            assert_ok!(IBChain::issue(Origin::signed(2), b"coin".to_vec(), 100, true));
+
+            <MyAssetsCount<Test>>::insert(3, u64::max_value());
+            assert_noop!(
+                IBChain::issue(Origin::signed(3), b"coin".to_vec(), 100, true),
+                "Overflow adding a new My Asset to total supply"
+            );
+        });
+    }
+
+    #[test]
+    fn disabled_events_skip_sent_asset_but_move_balances() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+            assert!(IBChain::events_enabled(asset_id));
+
+            assert_noop!(IBChain::set_events_enabled(Origin::signed(2), asset_id, false), "You do not own this asset");
+            assert_ok!(IBChain::set_events_enabled(Origin::signed(1), asset_id, false));
+
+            let seq = IBChain::event_seq();
+            assert_ok!(IBChain::sendasset(Origin::signed(1), 2, asset_id, 30));
+            assert_ok!(IBChain::send_asset_batch(Origin::signed(1), asset_id, vec![(3, 10)], false));
+
+            assert_eq!(IBChain::my_asset_balance((1, asset_id)), 60);
+            assert_eq!(IBChain::my_asset_balance((2, asset_id)), 30);
+            assert_eq!(IBChain::my_asset_balance((3, asset_id)), 10);
+            assert!(!ibchain_events().iter().any(|event| match event {
+                RawEvent::SentAsset(..) => true,
+                _ => false,
+            }));
+            // イベント番号は進む
+            assert_eq!(IBChain::event_seq(), seq + 2);
+
+            assert_ok!(IBChain::set_events_enabled(Origin::signed(1), asset_id, true));
+            assert_ok!(IBChain::sendasset(Origin::signed(1), 2, asset_id, 5));
+            assert_eq!(
+                ibchain_events().last(),
+                Some(&RawEvent::SentAsset(1, 2, asset_id, 5, seq + 2, 3))
+            );
+        });
+    }
+
+    #[test]
+    fn issue_named_derives_id_from_name() {
+        let issue_in_fresh_env = |prior_issues: u64| {
+            with_externalities(&mut new_test_ext(), || {
+                for _ in 0..prior_issues {
+                    assert_ok!(IBChain::issue(Origin::signed(2), b"other".to_vec(), 1, true));
+                }
+                assert_ok!(IBChain::issue_named(Origin::signed(1), b"gold".to_vec(), 100, true));
+                last_issued_asset(1)
+            })
+        };
+
+        let asset_id = issue_in_fresh_env(0);
+        assert_eq!(asset_id, BlakeTwo256::hash_of(&b"gold".to_vec()));
+        assert_eq!(issue_in_fresh_env(3), asset_id);
+    }
+
+    #[test]
+    fn issue_named_rejects_same_name_twice() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue_named(Origin::signed(1), b"gold".to_vec(), 100, true));
+            assert_noop!(
+                IBChain::issue_named(Origin::signed(2), b"gold".to_vec(), 100, true),
+                "Asset already exists"
+            );
+            assert_ok!(IBChain::issue_named(Origin::signed(2), b"silver".to_vec(), 100, true));
+        });
+    }
+
+    #[test]
+    fn owner_receives_own_asset_back_after_sending_everything() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+
+            assert_ok!(IBChain::sendasset(Origin::signed(1), 2, asset_id, 100));
+            assert_eq!(IBChain::my_asset_balance((1, asset_id)), 0);
+            assert_eq!(IBChain::holder_count(asset_id), 1);
+
+            assert_ok!(IBChain::sendasset(Origin::signed(2), 1, asset_id, 40));
+
+            // 登録は 1 件のまま、残高だけが戻る
+            assert_eq!(IBChain::my_asset_count(1), 1);
+            assert_eq!(IBChain::my_asset_by_index((1, 0)), asset_id);
+            assert_eq!(<MyAssetsIndex<Test>>::get((1, asset_id)), 0);
+            assert_eq!(IBChain::my_asset_balance((1, asset_id)), 40);
+            assert_eq!(IBChain::my_assets(&1), vec![(asset_id, 40)]);
+            assert_eq!(IBChain::holder_count(asset_id), 2);
+            assert_eq!(IBChain::owner_of(asset_id), Some(1));
+        });
+    }
+
+    #[test]
+    fn cooldown_blocks_rapid_sends() {
+        with_externalities(&mut new_test_ext(), || {
+            System::set_block_number(1);
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+            assert_noop!(IBChain::set_cooldown(Origin::signed(2), asset_id, 3), "You do not own this asset");
+            assert_ok!(IBChain::set_cooldown(Origin::signed(1), asset_id, 3));
+
+            assert_ok!(IBChain::sendasset(Origin::signed(1), 2, asset_id, 10));
+            System::set_block_number(3);
+            assert_noop!(IBChain::sendasset(Origin::signed(1), 2, asset_id, 10), "Transfer cooldown has not passed");
+            assert_noop!(
+                IBChain::send_asset_batch(Origin::signed(1), asset_id, vec![(3, 10)], false),
+                "Transfer cooldown has not passed"
+            );
+            // 間隔は送信者ごと
+            assert_ok!(IBChain::sendasset(Origin::signed(2), 3, asset_id, 5));
+
+            System::set_block_number(4);
+            assert_ok!(IBChain::sendasset(Origin::signed(1), 2, asset_id, 10));
+            assert_eq!(IBChain::last_send((1, asset_id)), Some(4));
+        });
+    }
+
+    #[test]
+    fn zero_cooldown_is_unrestricted() {
+        with_externalities(&mut new_test_ext(), || {
+            System::set_block_number(1);
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+
+            for _ in 0..3 {
+                assert_ok!(IBChain::sendasset(Origin::signed(1), 2, asset_id, 10));
+            }
+            assert_eq!(IBChain::last_send((1, asset_id)), None);
+            assert_eq!(IBChain::my_asset_balance((2, asset_id)), 30);
+        });
+    }
+
+    #[test]
+    fn sudo_set_balances_keeps_counters_consistent() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+
+            assert!(IBChain::sudo_set_balances(Origin::signed(1), asset_id, vec![(2, 10)]).is_err());
+            assert_ok!(IBChain::sudo_set_balances(Origin::ROOT, asset_id, vec![(1, 40), (2, 10), (3, 25)]));
+
+            assert_eq!(IBChain::total_issued_asset(asset_id), 75);
+            assert_eq!(IBChain::holder_count(asset_id), 3);
+            for (who, balance) in [(1u64, 40u64), (2, 10), (3, 25)].iter() {
+                assert_eq!(IBChain::my_assets(who), vec![(asset_id, *balance)]);
+            }
+
+            // 0 にすると保有者から外れる
+            assert_ok!(IBChain::sudo_set_balances(Origin::ROOT, asset_id, vec![(3, 0)]));
+            assert_eq!(IBChain::total_issued_asset(asset_id), 50);
+            assert_eq!(IBChain::holder_count(asset_id), 2);
+
+            assert_noop!(
+                IBChain::sudo_set_balances(Origin::ROOT, asset_id, vec![(2, 1), (2, 2)]),
+                "Duplicate account in entries"
+            );
+        });
+    }
+
+    #[test]
+    fn sendasset_rejects_reserved_accounts() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+
+            assert_noop!(
+                IBChain::sendasset(Origin::signed(1), IBChain::bridge_account(), asset_id, 10),
+                "Cannot send to a reserved account"
+            );
+            assert_noop!(
+                IBChain::send_asset_batch(Origin::signed(1), asset_id, vec![(2, 10), (IBChain::wrap_account(), 10)], false),
+                "Cannot send to a reserved account"
+            );
+            assert_ok!(IBChain::sendasset(Origin::signed(1), 2, asset_id, 10));
+            // 内部の移動は影響を受けない
+            assert_ok!(IBChain::lock_for_bridge(Origin::signed(1), asset_id, 10, b"0xdead".to_vec()));
+        });
+    }
+
+    #[test]
+    fn asset_serializes_to_expected_json() {
+        let asset = Asset {
+            id: H256::default(),
+            name: b"gold".to_vec(),
+            open: true,
+        };
+
+        let json = serde_json::to_value(&asset).unwrap();
+        assert_eq!(json, serde_json::json!({
+            "id": format!("0x{}", "0".repeat(64)),
+            "name": [103, 111, 108, 100],
+            "open": true,
+        }));
+        assert_eq!(serde_json::from_value::<Asset<H256>>(json).unwrap(), asset);
+    }
+
+    #[test]
+    fn asset_info_exposes_utf8_name_as_string() {
+        let asset = Asset {
+            id: H256::default(),
+            name: "金貨".as_bytes().to_vec(),
+            open: true,
+        };
+
+        let info = AssetInfo::from(asset.clone());
+        assert_eq!(info.name, "金貨");
+        assert!(info.name_is_utf8);
+        assert_eq!(info.name_bytes, asset.name);
+        assert_eq!(serde_json::to_value(&info).unwrap()["name"], serde_json::json!("金貨"));
+    }
+
+    #[test]
+    fn asset_info_falls_back_to_hex_for_binary_name() {
+        let asset = Asset {
+            id: H256::default(),
+            name: vec![0xff, 0x00, 0x1a],
+            open: false,
+        };
+
+        let info = AssetInfo::from(asset);
+        assert_eq!(info.name, "0xff001a");
+        assert!(!info.name_is_utf8);
+        assert_eq!(info.name_bytes, vec![0xff, 0x00, 0x1a]);
+    }
+
+    #[test]
+    fn compact_and_hex_ids_decode_to_the_same_hash() {
+        let id = BlakeTwo256::hash(b"gold");
+        let info = AssetInfo::from(Asset {
+            id,
+            name: b"gold".to_vec(),
+            open: true,
+        });
+
+        let hex_json = serde_json::to_value(&info).unwrap();
+        let compact_json = serde_json::to_value(&CompactAssetInfo::from(info)).unwrap();
+        let hex = hex_json["id"].as_str().unwrap();
+        let compact = compact_json["id"].as_str().unwrap();
+        assert_eq!(hex.len(), 66);
+        assert_eq!(compact.len(), 43);
+
+        let from_hex: H256 = serde_json::from_value(hex_json["id"].clone()).unwrap();
+        let from_compact = H256::from_slice(&decode_compact_id(compact).unwrap());
+        assert_eq!(from_hex, id);
+        assert_eq!(from_compact, id);
+        assert_eq!(compact_json["name"], hex_json["name"]);
+    }
+
+    #[test]
+    fn compact_id_round_trips_every_length() {
+        let bytes: Vec<u8> = (0..=255).collect();
+        for len in 0..8 {
+            let encoded = encode_compact_id(&bytes[250 - len..250]);
+            assert_eq!(decode_compact_id(&encoded), Some(bytes[250 - len..250].to_vec()));
+        }
+        assert_eq!(encode_compact_id(b"\xfb\xff"), "-_8");
+        assert_eq!(decode_compact_id("A"), None);
+        assert_eq!(decode_compact_id("AA=A"), None);
+    }
+
+    /// (1, 30), (2, 20), (3, 10) の 3 件を配布するエアドロップ
+    /// 戻り値は (資産 ID, エアドロップ ID, 各葉)
+    fn setup_airdrop() -> (H256, H256, [H256; 3]) {
+        assert_ok!(IBChain::issue(Origin::signed(9), b"coin".to_vec(), 100, true));
+        let asset_id = last_issued_asset(9);
+        let leaves = [
+            BlakeTwo256::hash_of(&(1u64, 30u64)),
+            BlakeTwo256::hash_of(&(2u64, 20u64)),
+            BlakeTwo256::hash_of(&(3u64, 10u64)),
+        ];
+        let root = merkle_node(merkle_node(leaves[0], leaves[1]), leaves[2]);
+        assert_ok!(IBChain::create_airdrop(Origin::signed(9), asset_id, root, 60));
+        let airdrop_id = ibchain_events().into_iter().filter_map(|event| match event {
+            RawEvent::AirdropCreated(_, airdrop_id, _, _) => Some(airdrop_id),
+            _ => None,
+        }).last().unwrap();
+        (asset_id, airdrop_id, leaves)
+    }
+
+    #[test]
+    fn claim_airdrop_with_valid_proof() {
+        with_externalities(&mut new_test_ext(), || {
+            let (asset_id, airdrop_id, leaves) = setup_airdrop();
+            assert_eq!(IBChain::my_asset_balance((9, asset_id)), 40);
+            assert_eq!(IBChain::my_asset_balance((IBChain::airdrop_account(), asset_id)), 60);
+
+            assert_ok!(IBChain::claim_airdrop(Origin::signed(2), airdrop_id, 20, vec![leaves[0], leaves[2]]));
+            assert_ok!(IBChain::claim_airdrop(Origin::signed(3), airdrop_id, 10, vec![merkle_node(leaves[0], leaves[1])]));
+
+            assert_eq!(IBChain::my_asset_balance((2, asset_id)), 20);
+            assert_eq!(IBChain::my_asset_balance((3, asset_id)), 10);
+            assert_eq!(IBChain::airdrop_remaining(airdrop_id), 30);
+        });
+    }
+
+    #[test]
+    fn claim_airdrop_rejects_double_claim() {
+        with_externalities(&mut new_test_ext(), || {
+            let (_, airdrop_id, leaves) = setup_airdrop();
+
+            assert_ok!(IBChain::claim_airdrop(Origin::signed(1), airdrop_id, 30, vec![leaves[1], leaves[2]]));
+            assert_noop!(
+                IBChain::claim_airdrop(Origin::signed(1), airdrop_id, 30, vec![leaves[1], leaves[2]]),
+                "Airdrop already claimed"
+            );
+        });
+    }
+
+    #[test]
+    fn claim_airdrop_rejects_invalid_proof() {
+        with_externalities(&mut new_test_ext(), || {
+            let (_, airdrop_id, leaves) = setup_airdrop();
+
+            // 量が違う
+            assert_noop!(
+                IBChain::claim_airdrop(Origin::signed(1), airdrop_id, 31, vec![leaves[1], leaves[2]]),
+                "Invalid airdrop proof"
+            );
+            // 他人の葉
+            assert_noop!(
+                IBChain::claim_airdrop(Origin::signed(4), airdrop_id, 30, vec![leaves[1], leaves[2]]),
+                "Invalid airdrop proof"
+            );
+            assert_noop!(
+                IBChain::claim_airdrop(Origin::signed(1), H256::default(), 30, vec![]),
+                "This airdrop does not exist"
+            );
+        });
+    }
+
+    #[test]
+    fn propose_new_owner_stores_proposal() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+
+            assert_noop!(IBChain::propose_new_owner(Origin::signed(2), asset_id, 2), "You do not own this asset");
+            assert_noop!(IBChain::propose_new_owner(Origin::signed(1), asset_id, 1), "You already own this asset");
+            assert_ok!(IBChain::propose_new_owner(Origin::signed(1), asset_id, 2));
+
+            assert_eq!(IBChain::pending_owner(asset_id), Some(2));
+            // 受諾までは変わらない
+            assert_eq!(IBChain::owner_of(asset_id), Some(1));
+        });
+    }
+
+    #[test]
+    fn only_proposed_account_can_accept_ownership() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"first".to_vec(), 100, true));
+            let first = last_issued_asset(1);
+            assert_ok!(IBChain::issue(Origin::signed(1), b"second".to_vec(), 100, true));
+            let second = last_issued_asset(1);
+
+            assert_noop!(IBChain::accept_ownership(Origin::signed(2), first), "No pending owner for this asset");
+            assert_ok!(IBChain::propose_new_owner(Origin::signed(1), first, 2));
+            assert_noop!(IBChain::accept_ownership(Origin::signed(3), first), "You are not the proposed owner");
+
+            assert_ok!(IBChain::accept_ownership(Origin::signed(2), first));
+            assert_eq!(IBChain::owner_of(first), Some(2));
+            assert_eq!(IBChain::pending_owner(first), None);
+            assert_eq!(IBChain::owned_assets(&1), vec![second]);
+            assert_eq!(<OwnedAssetsIndex<Test>>::get(second), 0);
+            assert_eq!(IBChain::owned_assets(&2), vec![first]);
+            // 所有量は変わらない
+            assert_eq!(IBChain::my_asset_balance((1, first)), 100);
+            assert_ok!(IBChain::issuemore(Origin::signed(2), first, 10));
+            assert_noop!(IBChain::issuemore(Origin::signed(1), first, 10), "You do not own this asset");
+        });
+    }
+
+    #[test]
+    fn total_volume_sums_transferred_quantities() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), u64::max_value(), true));
+            let asset_id = last_issued_asset(1);
+
+            assert_ok!(IBChain::sendasset(Origin::signed(1), 2, asset_id, 10));
+            assert_ok!(IBChain::sendasset(Origin::signed(2), 3, asset_id, 4));
+            assert_ok!(IBChain::send_asset_batch(Origin::signed(1), asset_id, vec![(3, 5), (4, 6)], false));
+            // 自分自身への送信は移動ではない
+            assert_ok!(IBChain::sendasset(Origin::signed(1), 1, asset_id, 100));
+            assert_eq!(IBChain::total_volume(asset_id), 25);
+
+            // u64 の合計ならあふれる量
+            assert_ok!(IBChain::sendasset(Origin::signed(1), 5, asset_id, u64::max_value() - 21));
+            assert_ok!(IBChain::sendasset(Origin::signed(5), 6, asset_id, u64::max_value() - 21));
+            assert_eq!(IBChain::total_volume(asset_id), 25 + 2 * u128::from(u64::max_value() - 21));
+
+            assert_eq!(IBChain::asset_stats(asset_id), AssetStats {
+                total_issued: u64::max_value(),
+                holder_count: 4,
+                total_volume: 25 + 2 * u128::from(u64::max_value() - 21),
+            });
+        });
+    }
+
+    #[test]
+    fn opted_out_recipient_cannot_receive() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+            assert_ok!(IBChain::issue(Origin::signed(1), b"other".to_vec(), 100, true));
+            let other = last_issued_asset(1);
+
+            assert_ok!(IBChain::opt_out(Origin::signed(2), asset_id));
+            assert_noop!(IBChain::sendasset(Origin::signed(1), 2, asset_id, 10), "Recipient opted out of this asset");
+            assert_noop!(
+                IBChain::send_asset_batch(Origin::signed(1), asset_id, vec![(3, 10), (2, 10)], false),
+                "Recipient opted out of this asset"
+            );
+            // 他の資産は受け取れる
+            assert_ok!(IBChain::sendasset(Origin::signed(1), 2, other, 10));
+
+            assert_ok!(IBChain::opt_in(Origin::signed(2), asset_id));
+            assert_ok!(IBChain::sendasset(Origin::signed(1), 2, asset_id, 10));
+            assert_eq!(IBChain::my_asset_balance((2, asset_id)), 10);
+        });
+    }
+
+    #[test]
+    fn issues_are_throttled_per_block() {
+        with_externalities(&mut new_test_ext(), || {
+            System::set_block_number(1);
+            assert!(IBChain::set_max_issues_per_block(Origin::signed(1), 2).is_err());
+            assert_ok!(IBChain::set_max_issues_per_block(Origin::ROOT, 2));
+
+            assert_ok!(IBChain::issue(Origin::signed(1), b"a".to_vec(), 1, true));
+            assert_ok!(IBChain::issue_named(Origin::signed(2), b"b".to_vec(), 1, true));
+            assert_noop!(IBChain::issue(Origin::signed(3), b"c".to_vec(), 1, true), "Too many issues in this block");
+
+            System::set_block_number(2);
+            <IBChain as OnInitialise<u64>>::on_initialise(2);
+            assert_eq!(IBChain::issues_this_block(), 0);
+            assert_noop!(
+                IBChain::issue_collection(Origin::signed(3), b"set".to_vec(), vec![(b"x".to_vec(), 1), (b"y".to_vec(), 1), (b"z".to_vec(), 1)]),
+                "Too many issues in this block"
+            );
+            assert_ok!(IBChain::issue(Origin::signed(3), b"c".to_vec(), 1, true));
+            assert_eq!(IBChain::issues_this_block(), 1);
+        });
+    }
+
+    #[test]
+    fn erc20_transfer_matches_sendasset() {
+        let run = |erc20: bool| {
+            with_externalities(&mut new_test_ext(), || {
+                assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+                let asset_id = last_issued_asset(1);
+                assert_ok!(IBChain::set_community_fund(Origin::signed(1), asset_id, Some(9), 1000));
+                if erc20 {
+                    assert_ok!(IBChain::transfer(Origin::signed(1), asset_id, 2, 30));
+                    assert_noop!(IBChain::transfer(Origin::signed(1), asset_id, 2, 71), "Your asset is less than you want to send the amount.");
+                } else {
+                    assert_ok!(IBChain::sendasset(Origin::signed(1), 2, asset_id, 30));
+                    assert_noop!(IBChain::sendasset(Origin::signed(1), 2, asset_id, 71), "Your asset is less than you want to send the amount.");
+                }
+                (
+                    [1u64, 2, 9].iter().map(|who| IBChain::balance_of(asset_id, who)).collect::<Vec<_>>(),
+                    IBChain::total_supply(asset_id),
+                    ibchain_events().len(),
+                )
+            })
+        };
+
+        assert_eq!(run(true), run(false));
+        assert_eq!(run(true).0, vec![70, 27, 3]);
+    }
+
+    #[test]
+    fn erc20_read_shims_match_storage() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+            assert_ok!(IBChain::approve(Origin::signed(1), 2, asset_id, 50));
+
+            assert_eq!(IBChain::balance_of(asset_id, &1), IBChain::my_asset_balance((1, asset_id)));
+            assert_eq!(IBChain::total_supply(asset_id), IBChain::total_issued_asset(asset_id));
+            assert_eq!(IBChain::allowance((1, 2, asset_id)), 50);
+        });
+    }
+
+    #[test]
+    fn erc20_transfer_from_spends_allowance() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+            assert_ok!(IBChain::approve(Origin::signed(1), 2, asset_id, 50));
+
+            assert_ok!(IBChain::transfer_from(Origin::signed(2), asset_id, 1, 3, 30));
+            assert_eq!(IBChain::balance_of(asset_id, &1), 70);
+            assert_eq!(IBChain::balance_of(asset_id, &3), 30);
+            assert_eq!(IBChain::allowance((1, 2, asset_id)), 20);
+
+            assert_noop!(
+                IBChain::transfer_from(Origin::signed(2), asset_id, 1, 3, 21),
+                "Allowance is less than you want to send"
+            );
+            assert_noop!(
+                IBChain::transfer_from(Origin::signed(4), asset_id, 1, 3, 1),
+                "Allowance is less than you want to send"
+            );
+        });
+    }
+
+    #[test]
+    fn top_holders_returns_largest_balances_in_order() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+            assert_ok!(IBChain::send_asset_batch(Origin::signed(1), asset_id, vec![(2, 30), (3, 5), (4, 40), (5, 15)], false));
+            // 所有量 0 の登録は除く
+            assert_ok!(IBChain::sendasset(Origin::signed(3), 6, asset_id, 5));
+
+            assert_eq!(IBChain::asset_holders_count(asset_id), 6);
+            assert_eq!(IBChain::top_holders(asset_id, 3), vec![(4, 40), (2, 30), (5, 15)]);
+            assert_eq!(IBChain::top_holders(asset_id, 100).len(), 5);
+        });
+    }
+
+    #[test]
+    fn create_and_mint_match_issue_and_issuemore() {
+        let run = |alias: bool| {
+            with_externalities(&mut new_test_ext(), || {
+                if alias {
+                    assert_ok!(IBChain::create(Origin::signed(1), b"coin".to_vec(), 100, true));
+                } else {
+                    assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+                }
+                let asset_id = last_issued_asset(1);
+                if alias {
+                    assert_ok!(IBChain::mint(Origin::signed(1), asset_id, 50));
+                    assert_noop!(IBChain::mint(Origin::signed(2), asset_id, 50), "You do not own this asset");
+                } else {
+                    assert_ok!(IBChain::issuemore(Origin::signed(1), asset_id, 50));
+                    assert_noop!(IBChain::issuemore(Origin::signed(2), asset_id, 50), "You do not own this asset");
+                }
+                (
+                    asset_id,
+                    IBChain::asset(asset_id),
+                    IBChain::total_issued_asset(asset_id),
+                    IBChain::my_asset_balance((1, asset_id)),
+                    ibchain_events(),
+                )
+            })
+        };
+
+        assert_eq!(run(true), run(false));
+    }
+
+    #[test]
+    fn account_lists_are_capped_and_flagged() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+            // 上限を超える登録数を直接作る (中身は既定値)
+            <MyAssetsCount<Test>>::insert(1, MAX_LIST_LEN + 5);
+            <OwnedAssetsCount<Test>>::insert(1, MAX_LIST_LEN + 5);
+            <MyAssetsArray<Test>>::insert((1, MAX_LIST_LEN + 4), asset_id);
+
+            let overview = IBChain::account_overview(&1);
+            assert_eq!(overview.held.len() as u64, MAX_LIST_LEN);
+            assert!(overview.held_truncated);
+            assert_eq!(overview.issued.len() as u64, MAX_LIST_LEN);
+            assert!(overview.issued_truncated);
+
+            // 残りはページで取得する
+            let rest = IBChain::my_assets_page(&1, MAX_LIST_LEN, MAX_LIST_LEN);
+            assert_eq!(rest.len(), 5);
+            assert_eq!(rest.last(), Some(&(asset_id, 100)));
+            assert_eq!(IBChain::owned_assets_page(&1, MAX_LIST_LEN + 3, 10).len(), 2);
+            assert!(!IBChain::account_overview(&2).held_truncated);
+        });
+    }
+
+    #[test]
+    fn failed_issue_writes_nothing() {
+        with_externalities(&mut new_test_ext(), || {
+            // 最後の確認 (所有資産数) だけが失敗するようにする
+            <MyAssetsCount<Test>>::insert(1, u64::max_value());
+            let asset_id = (System::random_seed(), 1u64, <Nonce<Test>>::get())
+                .using_encoded(BlakeTwo256::hash);
+
+            assert_noop!(
+                IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true),
+                "Overflow adding a new My Asset to total supply"
+            );
+
+            assert!(!<Assets<Test>>::exists(asset_id));
+            assert!(!<AssetOwner<Test>>::exists(asset_id));
+            assert!(!<AllAssetsArray<Test>>::exists(0));
+            assert_eq!(IBChain::all_asset_count(), 0);
+            assert!(!<AllAssetsIndex<Test>>::exists(asset_id));
+            assert!(!<OwnedAssetsArray<Test>>::exists((1, 0)));
+            assert_eq!(IBChain::owned_asset_count(1), 0);
+            assert!(!<OwnedAssetsIndex<Test>>::exists(asset_id));
+            assert!(!<TotalIssuedAssets<Test>>::exists(asset_id));
+            assert!(IBChain::supply_history(asset_id).is_empty());
+            assert_eq!(<Nonce<Test>>::get(), 0);
+            assert!(!<MyAssetsIndex<Test>>::exists((1, asset_id)));
+            assert!(!<MyAssetBalances<Test>>::exists((1, asset_id)));
+            assert_eq!(IBChain::my_asset_count(1), u64::max_value());
+            assert_eq!(IBChain::holder_count(asset_id), 0);
+            assert_eq!(IBChain::asset_holders_count(asset_id), 0);
+            assert_eq!(IBChain::issues_this_block(), 0);
+            assert_eq!(IBChain::event_seq(), 0);
+            assert!(ibchain_events().is_empty());
+        });
+    }
+
+    #[test]
+    fn max_holders_limits_new_holders_only() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+            assert_noop!(IBChain::set_max_holders(Origin::signed(2), asset_id, 3), "You do not own this asset");
+            assert_ok!(IBChain::set_max_holders(Origin::signed(1), asset_id, 3));
+
+            // 上限まで埋める
+            assert_ok!(IBChain::sendasset(Origin::signed(1), 2, asset_id, 10));
+            assert_ok!(IBChain::sendasset(Origin::signed(1), 3, asset_id, 10));
+            assert_eq!(IBChain::holder_count(asset_id), 3);
+
+            assert_noop!(IBChain::sendasset(Origin::signed(1), 4, asset_id, 10), "Max holders reached");
+            // 既存の保有者への送信はできる
+            assert_ok!(IBChain::sendasset(Origin::signed(1), 2, asset_id, 10));
+            // 全量送信は保有者数を変えない
+            assert_ok!(IBChain::sendasset(Origin::signed(3), 4, asset_id, 10));
+            assert_eq!(IBChain::holder_count(asset_id), 3);
+
+            assert_ok!(IBChain::set_max_holders(Origin::signed(1), asset_id, 0));
+            assert_ok!(IBChain::sendasset(Origin::signed(1), 5, asset_id, 10));
+        });
+    }
+
+    #[test]
+    fn owner_actions_are_logged_and_capped() {
+        with_externalities(&mut new_test_ext(), || {
+            System::set_block_number(1);
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+
+            assert_ok!(IBChain::issuemore(Origin::signed(1), asset_id, 10));
+            System::set_block_number(2);
+            assert_ok!(IBChain::set_cooldown(Origin::signed(1), asset_id, 5));
+            assert_ok!(IBChain::set_community_fund(Origin::signed(1), asset_id, Some(9), 100));
+            // 失敗した操作は記録しない
+            assert!(IBChain::set_cooldown(Origin::signed(2), asset_id, 5).is_err());
+
+            assert_eq!(IBChain::owner_action_log(asset_id), vec![
+                (1, b"issuemore".to_vec()),
+                (2, b"set_cooldown".to_vec()),
+                (2, b"set_community_fund".to_vec()),
+            ]);
+
+            for n in 0..MAX_OWNER_ACTIONS as u64 {
+                System::set_block_number(10 + n);
+                assert_ok!(IBChain::set_max_holders(Origin::signed(1), asset_id, n));
+            }
+            let log = IBChain::owner_action_log(asset_id);
+            assert_eq!(log.len(), MAX_OWNER_ACTIONS);
+            assert_eq!(log.first(), Some(&(10, b"set_max_holders".to_vec())));
+        });
+    }
+
+    #[test]
+    fn accepted_transfer_reaches_recipient() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+
+            assert_ok!(IBChain::propose_transfer(Origin::signed(1), 2, asset_id, 30));
+            let transfer_id = last_pending_transfer();
+            assert_eq!(IBChain::my_asset_balance((1, asset_id)), 70);
+            assert_eq!(IBChain::my_asset_balance((IBChain::escrow_account(), asset_id)), 30);
+            assert_eq!(IBChain::my_asset_balance((2, asset_id)), 0);
+
+            assert_noop!(IBChain::accept_transfer(Origin::signed(3), transfer_id), "You are not the recipient of this transfer");
+            assert_ok!(IBChain::accept_transfer(Origin::signed(2), transfer_id));
+            assert_eq!(IBChain::my_asset_balance((2, asset_id)), 30);
+            assert_eq!(IBChain::my_asset_balance((IBChain::escrow_account(), asset_id)), 0);
+            assert_eq!(IBChain::pending_transfer(transfer_id), None);
+            assert_noop!(IBChain::accept_transfer(Origin::signed(2), transfer_id), "This pending transfer does not exist");
+        });
+    }
+
+    #[test]
+    fn cancelled_transfer_refunds_sender() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+            assert_ok!(IBChain::propose_transfer(Origin::signed(1), 2, asset_id, 30));
+            let transfer_id = last_pending_transfer();
+
+            assert_noop!(IBChain::cancel_transfer(Origin::signed(2), transfer_id), "You are not the sender of this transfer");
+            assert_ok!(IBChain::cancel_transfer(Origin::signed(1), transfer_id));
+            assert_eq!(IBChain::my_asset_balance((1, asset_id)), 100);
+            assert_eq!(IBChain::pending_transfer(transfer_id), None);
+            assert_noop!(IBChain::accept_transfer(Origin::signed(2), transfer_id), "This pending transfer does not exist");
+        });
+    }
+
+    #[test]
+    fn escrowed_amount_cannot_be_spent_again() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+            assert_ok!(IBChain::propose_transfer(Origin::signed(1), 2, asset_id, 80));
+
+            assert_noop!(
+                IBChain::sendasset(Origin::signed(1), 3, asset_id, 21),
+                "Your asset is less than you want to send the amount."
+            );
+            assert_noop!(
+                IBChain::propose_transfer(Origin::signed(1), 3, asset_id, 21),
+                "Your asset is less than you want to send the amount."
+            );
+            assert_ok!(IBChain::sendasset(Origin::signed(1), 3, asset_id, 20));
+        });
+    }
+
+    #[test]
+    fn out_of_range_indices_are_none() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_eq!(IBChain::try_asset_by_index(0), None);
+            assert_eq!(IBChain::try_asset_of_owner_by_index(&1, 0), None);
+            assert_eq!(IBChain::try_my_asset_by_index(&1, 0), None);
+
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+
+            assert_eq!(IBChain::try_asset_by_index(0), Some(asset_id));
+            assert_eq!(IBChain::try_asset_of_owner_by_index(&1, 0), Some(asset_id));
+            assert_eq!(IBChain::try_my_asset_by_index(&1, 0), Some(asset_id));
+
+            assert_eq!(IBChain::try_asset_by_index(1), None);
+            assert_eq!(IBChain::try_asset_of_owner_by_index(&1, 1), None);
+            assert_eq!(IBChain::try_my_asset_by_index(&1, 1), None);
+            assert_eq!(IBChain::try_my_asset_by_index(&2, 0), None);
+            assert_eq!(IBChain::try_asset_by_index(u64::max_value()), None);
+            // 既定値のハッシュは資産として返らない
+            assert_eq!(IBChain::asset_by_index(1), H256::zero());
+        });
+    }
+
+    #[test]
+    fn genesis_assets_are_issued_with_fixed_supply() {
+        with_externalities(&mut ExtBuilder::default().genesis_asset(7, b"IB", 1_000).build(), || {
+            let asset_id = BlakeTwo256::hash_of(&b"IB".to_vec());
+            assert_eq!(IBChain::owner_of(asset_id), Some(7));
+            assert_eq!(IBChain::asset(asset_id).name, b"IB".to_vec());
+            assert!(!IBChain::asset(asset_id).open);
+            assert_eq!(IBChain::total_issued_asset(asset_id), 1_000);
+            assert_eq!(IBChain::my_asset_balance((7, asset_id)), 1_000);
+            assert_eq!(IBChain::all_asset_count(), 1);
+            assert_eq!(IBChain::issues_this_block(), 0);
+
+            assert_noop!(IBChain::issuemore(Origin::signed(7), asset_id, 1), "You can not issue more");
+        });
+    }
+
+    #[test]
+    fn genesis_asset_owner_can_also_be_endowed() {
+        // IB チェーンでは sudo キーが残高を持ち、ジェネシス資産の発行者でもある
+        let mut ext = ExtBuilder::default()
+            .native_balance(7, 1_000)
+            .genesis_asset(7, b"IB", 500)
+            .build();
+        with_externalities(&mut ext, || {
+            let asset_id = BlakeTwo256::hash_of(&b"IB".to_vec());
+            assert_eq!(Balances::free_balance(&7), 1_000);
+            assert_eq!(IBChain::my_asset_balance((7, asset_id)), 500);
+            assert_eq!(IBChain::my_assets(&7), vec![(asset_id, 500)]);
+            assert_eq!(IBChain::owned_assets(&7), vec![asset_id]);
+            assert_eq!(IBChain::holder_count(asset_id), 1);
+            assert_eq!(IBChain::asset_holders_count(asset_id), 1);
+            assert!(IBChain::verify_asset(asset_id));
+
+            // 資産の送信は残高に影響しない
+            assert_ok!(IBChain::sendasset(Origin::signed(7), 2, asset_id, 100));
+            assert_eq!(Balances::free_balance(&7), 1_000);
+            assert_eq!(IBChain::my_asset_balance((7, asset_id)), 400);
+        });
+    }
+
+    #[test]
+    fn verify_asset_detects_corrupted_balance() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+            assert_ok!(IBChain::sendasset(Origin::signed(1), 2, asset_id, 40));
+            assert_ok!(IBChain::issuemore(Origin::signed(1), asset_id, 10));
+            assert_ok!(IBChain::burn(Origin::signed(2), asset_id, 5));
+            assert!(IBChain::verify_asset(asset_id));
+
+            // 発行済量を変えずに所有量だけを書き換える
+            <MyAssetBalances<Test>>::insert((2, asset_id), 36);
+            assert!(!IBChain::verify_asset(asset_id));
+        });
+    }
+
+    #[test]
+    fn allowlist_mode_gates_both_parties() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+            assert_ok!(IBChain::sendasset(Origin::signed(1), 2, asset_id, 30));
+
+            assert_noop!(IBChain::set_requires_allowlist(Origin::signed(2), asset_id, true), "You do not own this asset");
+            assert_noop!(IBChain::set_allowlisted(Origin::signed(2), asset_id, 2, true), "You do not own this asset");
+            assert_ok!(IBChain::set_requires_allowlist(Origin::signed(1), asset_id, true));
+
+            // 発行者から許可されていない受信者へ
+            assert_noop!(IBChain::sendasset(Origin::signed(1), 3, asset_id, 10), "Recipient is not allowlisted for this asset");
+            // 許可されていない送信者から発行者へ
+            assert_noop!(IBChain::sendasset(Origin::signed(2), 1, asset_id, 10), "Sender is not allowlisted for this asset");
+            assert_noop!(
+                IBChain::send_asset_batch(Origin::signed(1), asset_id, vec![(3, 10)], false),
+                "Recipient is not allowlisted for this asset"
+            );
+
+            assert_ok!(IBChain::set_allowlisted(Origin::signed(1), asset_id, 2, true));
+            assert_ok!(IBChain::set_allowlisted(Origin::signed(1), asset_id, 3, true));
+            assert_ok!(IBChain::sendasset(Origin::signed(2), 3, asset_id, 10));
+            assert_ok!(IBChain::sendasset(Origin::signed(2), 1, asset_id, 10));
+            assert_eq!(IBChain::my_asset_balance((3, asset_id)), 10);
+
+            // 許可の取り消し
+            assert_ok!(IBChain::set_allowlisted(Origin::signed(1), asset_id, 3, false));
+            assert_noop!(IBChain::sendasset(Origin::signed(2), 3, asset_id, 1), "Recipient is not allowlisted for this asset");
+
+            // 許可リスト制の解除
+            assert_ok!(IBChain::set_requires_allowlist(Origin::signed(1), asset_id, false));
+            assert_ok!(IBChain::sendasset(Origin::signed(2), 4, asset_id, 1));
+            assert_eq!(IBChain::my_asset_balance((4, asset_id)), 1);
+        });
+    }
+
+    #[test]
+    fn summarized_batch_emits_one_event() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+            let before = ibchain_events().len();
+            let seq = IBChain::event_seq();
+
+            assert_ok!(IBChain::send_asset_batch(Origin::signed(1), asset_id, vec![(2, 10), (3, 20), (4, 5)], true));
+
+            assert_eq!(ibchain_events()[before..].to_vec(), vec![RawEvent::BatchSent(asset_id, 1, 3, 35)]);
+            assert_eq!(IBChain::event_seq(), seq);
+            assert_eq!(IBChain::my_asset_balance((3, asset_id)), 20);
+            assert_eq!(IBChain::my_asset_balance((1, asset_id)), 65);
+
+            // 分配も 1 件にまとめる
+            assert_ok!(IBChain::set_community_fund(Origin::signed(1), asset_id, Some(9), 1_000));
+            let before = ibchain_events().len();
+            assert_ok!(IBChain::send_asset_batch(Origin::signed(1), asset_id, vec![(2, 10), (3, 20)], true));
+            assert_eq!(ibchain_events()[before..].to_vec(), vec![
+                RawEvent::BatchSent(asset_id, 1, 2, 30),
+                RawEvent::CommunityFunded(1, 9, asset_id, 3),
+            ]);
+            assert_eq!(IBChain::my_asset_balance((9, asset_id)), 3);
+        });
+    }
+
+    #[test]
+    fn owner_holding_none_gets_specific_error() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+            assert_ok!(IBChain::propose_new_owner(Origin::signed(1), asset_id, 2));
+            assert_ok!(IBChain::accept_ownership(Origin::signed(2), asset_id));
+
+            assert_noop!(IBChain::sendasset(Origin::signed(2), 3, asset_id, 1), "You hold none of this asset");
+            assert_noop!(
+                IBChain::send_asset_batch(Origin::signed(2), asset_id, vec![(3, 1)], false),
+                "You hold none of this asset"
+            );
+            // 発行者でないアカウントには従来どおり
+            assert_noop!(IBChain::sendasset(Origin::signed(3), 4, asset_id, 1), "This asset does not exist");
+        });
+    }
+
+    #[test]
+    fn restricted_destinations_limit_recipients_only() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+            assert_ok!(IBChain::sendasset(Origin::signed(1), 2, asset_id, 30));
+
+            assert_noop!(IBChain::set_restrict_destinations(Origin::signed(2), asset_id, true), "You do not own this asset");
+            assert_ok!(IBChain::set_restrict_destinations(Origin::signed(1), asset_id, true));
+            assert_ok!(IBChain::set_allowed_destination(Origin::signed(1), asset_id, 5, true));
+
+            assert_noop!(IBChain::sendasset(Origin::signed(2), 3, asset_id, 10), "Destination is not allowed for this asset");
+            assert_noop!(
+                IBChain::send_asset_batch(Origin::signed(1), asset_id, vec![(5, 1), (3, 1)], false),
+                "Destination is not allowed for this asset"
+            );
+
+            // 送信者は制限されない。発行者への送信は常に可能
+            assert_ok!(IBChain::sendasset(Origin::signed(2), 5, asset_id, 10));
+            assert_ok!(IBChain::sendasset(Origin::signed(5), 1, asset_id, 4));
+            assert_eq!(IBChain::my_asset_balance((5, asset_id)), 6);
+
+            assert_ok!(IBChain::set_restrict_destinations(Origin::signed(1), asset_id, false));
+            assert_ok!(IBChain::sendasset(Origin::signed(2), 3, asset_id, 10));
+        });
+    }
+
+    #[test]
+    fn owned_assets_index_stays_consistent_across_owners() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"a".to_vec(), 100, true));
+            let a = last_issued_asset(1);
+            assert_ok!(IBChain::issue(Origin::signed(1), b"b".to_vec(), 100, true));
+            let b = last_issued_asset(1);
+            assert_ok!(IBChain::issue(Origin::signed(2), b"c".to_vec(), 100, true));
+            let c = last_issued_asset(2);
+            // a と c はどちらも各オーナーの配列の 0 番目
+            assert_eq!(<OwnedAssetsIndex<Test>>::get(a), 0);
+            assert_eq!(<OwnedAssetsIndex<Test>>::get(c), 0);
+
+            assert_ok!(IBChain::propose_new_owner(Origin::signed(1), a, 2));
+            assert_ok!(IBChain::accept_ownership(Origin::signed(2), a));
+            assert_ok!(IBChain::propose_new_owner(Origin::signed(2), c, 1));
+            assert_ok!(IBChain::accept_ownership(Origin::signed(1), c));
+
+            assert_eq!(IBChain::owned_assets(&1), vec![b, c]);
+            assert_eq!(IBChain::owned_assets(&2), vec![a]);
+            for owner in [1u64, 2].iter() {
+                for i in 0..IBChain::owned_asset_count(owner) {
+                    let asset_id = IBChain::asset_of_owner_by_index((*owner, i));
+                    assert_eq!(<OwnedAssetsIndex<Test>>::get(asset_id), i);
+                    assert_eq!(IBChain::owner_of(asset_id), Some(*owner));
+                }
+            }
+        });
+    }
+
+    #[test]
+    fn mint_capacity_reflects_owner_and_open() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"open".to_vec(), 100, true));
+            let open = last_issued_asset(1);
+            assert_ok!(IBChain::issue(Origin::signed(1), b"closed".to_vec(), 100, false));
+            let closed = last_issued_asset(1);
+
+            assert_eq!(IBChain::mint_capacity(&2, open), None);
+            assert_eq!(IBChain::mint_capacity(&1, closed), None);
+            assert_eq!(IBChain::mint_capacity(&1, H256::zero()), None);
+            assert_eq!(IBChain::mint_capacity(&1, open), Some(u64::max_value() - 100));
+
+            assert_ok!(IBChain::issuemore(Origin::signed(1), open, u64::max_value() - 100));
+            assert_eq!(IBChain::mint_capacity(&1, open), Some(0));
+        });
+    }
+
+    #[test]
+    fn pay_invoice_records_payment_once() {
+        with_externalities(&mut new_test_ext(), || {
+            System::set_block_number(3);
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+            let invoice_id = BlakeTwo256::hash_of(&1u32);
+
+            assert_eq!(IBChain::invoice(invoice_id), None);
+            assert_ok!(IBChain::pay_invoice(Origin::signed(1), 2, asset_id, 40, invoice_id));
+            assert_eq!(IBChain::invoice(invoice_id), Some((1, 2, asset_id, 40, 3)));
+            assert_eq!(IBChain::my_asset_balance((2, asset_id)), 40);
+
+            assert_noop!(
+                IBChain::pay_invoice(Origin::signed(1), 2, asset_id, 40, invoice_id),
+                "This invoice has already been paid"
+            );
+            // 送信に失敗した場合は記録しない
+            let other = BlakeTwo256::hash_of(&2u32);
+            assert_noop!(
+                IBChain::pay_invoice(Origin::signed(1), 2, asset_id, 61, other),
+                "Your asset is less than you want to send the amount."
+            );
+            assert_eq!(IBChain::invoice(other), None);
+        });
+    }
+
+    #[test]
+    fn migrate_holder_count_backfills_from_holder_index() {
+        with_externalities(&mut new_test_ext(), || {
+            // HolderCount 導入前の状態: 保有者と所有量はあるが保有者数が 0
+            let asset_id = BlakeTwo256::hash_of(&b"legacy".to_vec());
+            let other = BlakeTwo256::hash_of(&b"legacy2".to_vec());
+            for (i, (who, balance)) in [(1u64, 50u64), (2, 0), (3, 25)].iter().enumerate() {
+                <AssetHoldersArray<Test>>::insert((asset_id, i as u64), who);
+                <MyAssetBalances<Test>>::insert((*who, asset_id), balance);
+            }
+            <AssetHoldersCount<Test>>::insert(asset_id, 3);
+            <AssetHoldersArray<Test>>::insert((other, 0), 4);
+            <MyAssetBalances<Test>>::insert((4, other), 9);
+            <AssetHoldersCount<Test>>::insert(other, 1);
+            assert_eq!(IBChain::holder_count(asset_id), 0);
+
+            assert!(IBChain::migrate_holder_count(Origin::signed(1), vec![asset_id]).is_err());
+            assert_ok!(IBChain::migrate_holder_count(Origin::ROOT, vec![asset_id, other]));
+            assert_eq!(IBChain::holder_count(asset_id), 2);
+            assert_eq!(IBChain::holder_count(other), 1);
+
+            // 再実行しても変わらない
+            assert_ok!(IBChain::migrate_holder_count(Origin::ROOT, vec![asset_id]));
+            assert_eq!(IBChain::holder_count(asset_id), 2);
+        });
+    }
+
+    #[test]
+    fn spender_approvals_follow_allowances() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"a".to_vec(), 100, true));
+            let a = last_issued_asset(1);
+            assert_ok!(IBChain::issue(Origin::signed(2), b"b".to_vec(), 100, true));
+            let b = last_issued_asset(2);
+
+            assert_ok!(IBChain::approve(Origin::signed(1), 9, a, 30));
+            assert_ok!(IBChain::approve(Origin::signed(2), 9, b, 10));
+            assert_eq!(IBChain::approvals_for_spender(&9), vec![(1, a, 30), (2, b, 10)]);
+
+            // 上書きしても重複しない
+            assert_ok!(IBChain::approve(Origin::signed(1), 9, a, 20));
+            assert_eq!(IBChain::approvals_for_spender(&9), vec![(1, a, 20), (2, b, 10)]);
+
+            // 使い切ると消える
+            assert_ok!(IBChain::transfer_from(Origin::signed(9), a, 1, 3, 20));
+            assert_eq!(IBChain::approvals_for_spender(&9), vec![(2, b, 10)]);
+            assert_ok!(IBChain::burn_from(Origin::signed(9), 2, b, 4));
+            assert_eq!(IBChain::approvals_for_spender(&9), vec![(2, b, 6)]);
+
+            // 0 の承認で取り消す
+            assert_ok!(IBChain::approve(Origin::signed(2), 9, b, 0));
+            assert!(IBChain::approvals_for_spender(&9).is_empty());
+            assert!(!<Allowances<Test>>::exists((2, 9, b)));
+        });
+    }
+
+    #[test]
+    fn sponsored_transfer_pays_relayer_and_moves_asset() {
+        with_externalities(&mut ExtBuilder::default().native_balance(1, 100).native_balance(9, 1).build(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+
+            assert_ok!(IBChain::sponsored_transfer(Origin::signed(1), 2, asset_id, 30, 9, 15));
+            assert_eq!(IBChain::my_asset_balance((2, asset_id)), 30);
+            assert_eq!(Balances::free_balance(&1), 85);
+            assert_eq!(Balances::free_balance(&9), 16);
+        });
+    }
+
+    #[test]
+    fn sponsored_transfer_without_native_funds_moves_nothing() {
+        with_externalities(&mut ExtBuilder::default().native_balance(1, 10).native_balance(9, 1).build(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+
+            assert_noop!(
+                IBChain::sponsored_transfer(Origin::signed(1), 2, asset_id, 30, 9, 11),
+                "Insufficient native balance for the relayer fee"
+            );
+            assert_noop!(
+                IBChain::sponsored_transfer(Origin::signed(1), 2, asset_id, 30, 8, 1),
+                "Relayer account does not exist"
+            );
+            // 資産の送信に失敗した場合も手数料は払わない
+            assert_noop!(
+                IBChain::sponsored_transfer(Origin::signed(1), 2, asset_id, 101, 9, 5),
+                "Your asset is less than you want to send the amount."
+            );
+            assert_eq!(IBChain::my_asset_balance((2, asset_id)), 0);
+            assert_eq!(Balances::free_balance(&1), 10);
+            assert_eq!(Balances::free_balance(&9), 1);
+        });
+    }
+
+    #[test]
+    fn sponsored_transfer_from_a_vesting_sender_moves_nothing() {
+        let mut ext = ExtBuilder::default().native_balance(1, 100).native_balance(9, 1).vesting(1, 10, 20).build();
+        with_externalities(&mut ext, || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+
+            // 残高は足りるが、権利確定前のため make_transfer が失敗する
+            assert!(IBChain::sponsored_transfer(Origin::signed(1), 2, asset_id, 30, 9, 15).is_err());
+            assert_eq!(IBChain::my_asset_balance((1, asset_id)), 100);
+            assert_eq!(IBChain::my_asset_balance((2, asset_id)), 0);
+            assert_eq!(Balances::free_balance(&1), 100);
+            assert_eq!(Balances::free_balance(&9), 1);
+        });
+    }
+
+    #[test]
+    fn issuemore_respects_per_call_cap() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+
+            assert_noop!(IBChain::set_max_mint_per_call(Origin::signed(2), asset_id, 50), "You do not own this asset");
+            assert_ok!(IBChain::set_max_mint_per_call(Origin::signed(1), asset_id, 50));
+
+            assert_noop!(IBChain::issuemore(Origin::signed(1), asset_id, 51), "Mint exceeds per-call cap");
+            assert_ok!(IBChain::issuemore(Origin::signed(1), asset_id, 50));
+            assert_ok!(IBChain::issuemore(Origin::signed(1), asset_id, 50));
+            assert_eq!(IBChain::total_issued_asset(asset_id), 200);
+
+            assert_ok!(IBChain::set_max_mint_per_call(Origin::signed(1), asset_id, 0));
+            assert_ok!(IBChain::issuemore(Origin::signed(1), asset_id, 1_000));
+        });
+    }
+
+    #[test]
+    fn total_and_spendable_track_vote_lock_expiry() {
+        with_externalities(&mut new_test_ext(), || {
+            System::set_block_number(1);
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+            assert_eq!(IBChain::total_and_spendable(&1, asset_id), (100, 100));
+            assert_eq!(IBChain::total_and_spendable(&2, asset_id), (0, 0));
+
+            assert_ok!(IBChain::lock_for_vote(Origin::signed(1), asset_id, 60, 5));
+            assert_eq!(IBChain::total_and_spendable(&1, asset_id), (100, 40));
+
+            System::set_block_number(4);
+            assert_eq!(IBChain::total_and_spendable(&1, asset_id), (100, 40));
+            // 期限を過ぎたロックは解除前でも差し引かない
+            System::set_block_number(5);
+            assert_eq!(IBChain::total_and_spendable(&1, asset_id), (100, 100));
+        });
+    }
+
+    #[test]
+    fn issue_ids_stay_distinct_under_constant_seed() {
+        with_externalities(&mut new_test_ext(), || {
+            // モックでは random_seed が変わらないため、ID の違いは Nonce だけから生まれる
+            let seed = System::random_seed();
+            let mut ids = vec![];
+            for i in 0..5u8 {
+                assert_ok!(IBChain::issue(Origin::signed(1), vec![b'a' + i], 10, true));
+                ids.push(last_issued_asset(1));
+                // 失敗した発行は Nonce を進めないが、次の成功した発行の ID とも重ならない
+                assert!(IBChain::issue(Origin::signed(1), b"bad".to_vec(), 0, true).is_err());
+            }
+            assert_eq!(System::random_seed(), seed);
+            assert_eq!(<Nonce<Test>>::get(), 5);
+
+            let mut unique = ids.clone();
+            unique.sort();
+            unique.dedup();
+            assert_eq!(unique.len(), ids.len());
+            assert_eq!(IBChain::owned_assets(&1), ids);
+        });
+    }
+
+    #[test]
+    fn memo_required_asset_only_moves_with_memo() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+            assert!(!IBChain::require_memo(asset_id));
+
+            assert_noop!(IBChain::set_require_memo(Origin::signed(2), asset_id, true), "You do not own this asset");
+            assert_ok!(IBChain::set_require_memo(Origin::signed(1), asset_id, true));
+
+            assert_noop!(IBChain::sendasset(Origin::signed(1), 2, asset_id, 10), "Memo required");
+            assert_noop!(IBChain::transfer(Origin::signed(1), asset_id, 2, 10), "Memo required");
+            assert_noop!(IBChain::send_asset_batch(Origin::signed(1), asset_id, vec![(2, 10)], false), "Memo required");
+            assert_noop!(IBChain::propose_transfer(Origin::signed(1), 2, asset_id, 10), "Memo required");
+            assert_noop!(<IBChain as Fungible<u64, H256>>::transfer(&1, &2, asset_id, 10), "Memo required");
+            assert_noop!(IBChain::send_asset_with_memo(Origin::signed(1), 2, asset_id, 10, vec![]), "Memo must not be empty");
+            assert_noop!(
+                IBChain::send_asset_with_memo(Origin::signed(1), 2, asset_id, 10, vec![0; MAX_MEMO_LEN + 1]),
+                "Memo is too long"
+            );
+
+            assert_ok!(IBChain::send_asset_with_memo(Origin::signed(1), 2, asset_id, 10, b"deposit-42".to_vec()));
+            assert_eq!(IBChain::my_asset_balance((2, asset_id)), 10);
+            assert_eq!(
+                ibchain_events().last(),
+                Some(&RawEvent::SentWithMemo(1, 2, asset_id, 10, b"deposit-42".to_vec()))
+            );
+
+            assert_ok!(IBChain::set_require_memo(Origin::signed(1), asset_id, false));
+            assert_ok!(IBChain::sendasset(Origin::signed(1), 2, asset_id, 10));
+        });
+    }
+
+    #[test]
+    fn committee_action_waits_for_threshold() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+
+            assert_noop!(
+                IBChain::set_committee(Origin::signed(1), asset_id, vec![2, 3, 4], 4),
+                "Threshold must be between 1 and the committee size"
+            );
+            assert_noop!(IBChain::set_committee(Origin::signed(1), asset_id, vec![2, 2], 1), "Duplicate committee member");
+            assert_ok!(IBChain::set_committee(Origin::signed(1), asset_id, vec![2, 3, 4], 2));
+
+            assert_noop!(
+                IBChain::propose_admin_action(Origin::signed(5), asset_id, AdminAction::Mint(50)),
+                "You are not a committee member"
+            );
+            assert_ok!(IBChain::propose_admin_action(Origin::signed(2), asset_id, AdminAction::Mint(50)));
+            let proposal_id = last_admin_proposal();
+            // 1 人の承認では実行されない
+            assert_eq!(IBChain::total_issued_asset(asset_id), 100);
+
+            assert_noop!(IBChain::approve_admin_action(Origin::signed(5), proposal_id), "You are not a committee member");
+            assert_noop!(
+                IBChain::approve_admin_action(Origin::signed(2), proposal_id),
+                "You have already approved this proposal"
+            );
+
+            assert_ok!(IBChain::approve_admin_action(Origin::signed(3), proposal_id));
+            assert_eq!(IBChain::total_issued_asset(asset_id), 150);
+            assert_eq!(IBChain::my_asset_balance((1, asset_id)), 150);
+            assert_eq!(IBChain::admin_proposal(proposal_id), None);
+            assert_noop!(
+                IBChain::approve_admin_action(Origin::signed(4), proposal_id),
+                "This admin proposal does not exist"
+            );
+        });
+    }
+
+    #[test]
+    fn committee_action_fails_like_owner_call() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, false));
+            let asset_id = last_issued_asset(1);
+            assert_ok!(IBChain::set_committee(Origin::signed(1), asset_id, vec![2, 3], 2));
+
+            assert_ok!(IBChain::propose_admin_action(Origin::signed(2), asset_id, AdminAction::Mint(50)));
+            let proposal_id = last_admin_proposal();
+            assert_noop!(IBChain::approve_admin_action(Origin::signed(3), proposal_id), "You can not issue more");
+
+            assert_ok!(IBChain::propose_admin_action(Origin::signed(2), asset_id, AdminAction::SetMaxHolders(5)));
+            assert_ok!(IBChain::approve_admin_action(Origin::signed(3), last_admin_proposal()));
+            assert_eq!(IBChain::max_holders(asset_id), 5);
+        });
+    }
+
+    #[test]
+    fn empty_batches_are_rejected() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+            let events = ibchain_events().len();
+
+            assert_noop!(IBChain::send_asset_batch(Origin::signed(1), asset_id, vec![], false), "Empty batch");
+            assert_noop!(IBChain::send_asset_batch(Origin::signed(1), asset_id, vec![], true), "Empty batch");
+            assert_noop!(IBChain::sudo_set_balances(Origin::ROOT, asset_id, vec![]), "Empty batch");
+            assert_noop!(IBChain::migrate_holder_count(Origin::ROOT, vec![]), "Empty batch");
+            assert_noop!(
+                IBChain::issue_collection(Origin::signed(1), b"armoury".to_vec(), vec![]),
+                "Collection has no items"
+            );
+            assert_eq!(ibchain_events().len(), events);
+        });
+    }
+
+    #[test]
+    fn snapshot_reads_follow_the_queried_state() {
+        // 提案作成時点の状態
+        let mut at_proposal = new_test_ext();
+        let asset_id = with_externalities(&mut at_proposal, || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+            assert_ok!(IBChain::sendasset(Origin::signed(1), 2, asset_id, 10));
+            asset_id
+        });
+        // その後の状態 (同じ操作に続けて送信・追加発行)
+        let mut latest = new_test_ext();
+        with_externalities(&mut latest, || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            assert_eq!(last_issued_asset(1), asset_id);
+            assert_ok!(IBChain::sendasset(Origin::signed(1), 2, asset_id, 10));
+            assert_ok!(IBChain::sendasset(Origin::signed(1), 2, asset_id, 30));
+            assert_ok!(IBChain::issuemore(Origin::signed(1), asset_id, 50));
+        });
+
+        with_externalities(&mut at_proposal, || {
+            assert_eq!(IBChain::balance_at(&2, asset_id), 10);
+            assert_eq!(IBChain::total_supply_at(asset_id), 100);
+        });
+        with_externalities(&mut latest, || {
+            assert_eq!(IBChain::balance_at(&2, asset_id), 40);
+            assert_eq!(IBChain::total_supply_at(asset_id), 150);
+        });
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "holder balance exceeds the issued total")]
+    fn send_into_drifted_balance_trips_debug_assertion() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+
+            // 発行済量と合わない所有量
+            <MyAssetBalances<Test>>::insert((2, asset_id), 95);
+            let _ = IBChain::sendasset(Origin::signed(1), 2, asset_id, 10);
+        });
+    }
+
+    #[test]
+    fn issue_and_distribute_seeds_initial_holders() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue_and_distribute(
+                Origin::signed(1), b"sale".to_vec(), false, vec![(2, 50), (1, 20), (3, 30)]
+            ));
+            let asset_id = last_issued_asset(1);
+
+            assert_eq!(IBChain::owner_of(asset_id), Some(1));
+            assert_eq!(IBChain::total_issued_asset(asset_id), 100);
+            assert_eq!(IBChain::my_asset_balance((1, asset_id)), 20);
+            assert_eq!(IBChain::my_asset_balance((2, asset_id)), 50);
+            assert_eq!(IBChain::my_asset_balance((3, asset_id)), 30);
+            assert_eq!(IBChain::holder_count(asset_id), 3);
+            assert!(IBChain::verify_asset(asset_id));
+        });
+    }
+
+    #[test]
+    fn issue_and_distribute_validates_before_issuing() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_noop!(
+                IBChain::issue_and_distribute(Origin::signed(1), b"sale".to_vec(), false, vec![(2, 1), (2, 1)]),
+                "Duplicate account in distribution"
+            );
+            assert_noop!(
+                IBChain::issue_and_distribute(Origin::signed(1), b"sale".to_vec(), false, vec![(2, u64::max_value()), (3, 1)]),
+                "Overflow adding the distribution total"
+            );
+            assert_noop!(
+                IBChain::issue_and_distribute(Origin::signed(1), b"sale".to_vec(), false, vec![]),
+                "Empty batch"
+            );
+            assert_eq!(IBChain::all_asset_count(), 0);
+
+            // 発行者に配布しない場合、発行者は保有者に残らない
+            assert_ok!(IBChain::issue_and_distribute(Origin::signed(1), b"sale".to_vec(), false, vec![(2, 10)]));
+            let asset_id = last_issued_asset(1);
+            assert_eq!(IBChain::my_asset_balance((1, asset_id)), 0);
+            assert_eq!(IBChain::holder_count(asset_id), 1);
+        });
+    }
+
+    #[test]
+    fn issuemore_does_not_register_asset_again() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+            assert_eq!(IBChain::all_asset_count(), 1);
+
+            for i in 1..4 {
+                assert_ok!(IBChain::issuemore(Origin::signed(1), asset_id, 10));
+                assert_eq!(IBChain::total_issued_asset(asset_id), 100 + 10 * i);
+                assert_eq!(IBChain::all_asset_count(), 1);
+                assert_eq!(<AllAssetsIndex<Test>>::get(asset_id), 0);
+            }
+        });
+    }
+
+    #[test]
+    fn issuemore_keeps_owned_and_held_counts() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+
+            assert_ok!(IBChain::issuemore(Origin::signed(1), asset_id, 10));
+            assert_ok!(IBChain::issuemore(Origin::signed(1), asset_id, 10));
+            assert_eq!(IBChain::owned_asset_count(&1), 1);
+            assert_eq!(IBChain::owned_assets(&1), vec![asset_id]);
+            assert_eq!(IBChain::my_asset_count(&1), 1);
+            assert_eq!(IBChain::my_asset_balance((1, asset_id)), 120);
+        });
+    }
+
+    #[test]
+    fn escrows_index_tracks_both_parties() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+
+            assert_ok!(IBChain::propose_transfer(Origin::signed(1), 2, asset_id, 10));
+            let first = last_pending_transfer();
+            assert_ok!(IBChain::propose_transfer(Origin::signed(1), 3, asset_id, 10));
+            let second = last_pending_transfer();
+            assert_eq!(IBChain::escrows_of(&1), vec![first, second]);
+            assert_eq!(IBChain::escrows_of(&2), vec![first]);
+            assert_eq!(IBChain::escrows_of(&3), vec![second]);
+
+            assert_ok!(IBChain::cancel_transfer(Origin::signed(1), first));
+            assert_eq!(IBChain::escrows_of(&1), vec![second]);
+            assert!(IBChain::escrows_of(&2).is_empty());
+
+            assert_ok!(IBChain::accept_transfer(Origin::signed(3), second));
+            assert!(IBChain::escrows_of(&1).is_empty());
+            assert!(IBChain::escrows_of(&3).is_empty());
+        });
+    }
+
+    #[test]
+    fn send_asset_or_fallback_routes_rejected_transfers() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+            assert_ok!(IBChain::opt_out(Origin::signed(3), asset_id));
+
+            // 受取先がなければ sendasset と同じく失敗する
+            assert_noop!(
+                IBChain::send_asset_or_fallback(Origin::signed(1), 3, asset_id, 10),
+                "Recipient opted out of this asset"
+            );
+
+            assert_noop!(IBChain::set_fallback_account(Origin::signed(2), asset_id, Some(9)), "You do not own this asset");
+            assert_ok!(IBChain::set_fallback_account(Origin::signed(1), asset_id, Some(9)));
+
+            // 通常の送信
+            assert_ok!(IBChain::send_asset_or_fallback(Origin::signed(1), 2, asset_id, 10));
+            assert_eq!(IBChain::my_asset_balance((2, asset_id)), 10);
+
+            // 受信者が拒否している場合は受取先へ
+            assert_ok!(IBChain::send_asset_or_fallback(Origin::signed(1), 3, asset_id, 15));
+            assert_eq!(IBChain::my_asset_balance((3, asset_id)), 0);
+            assert_eq!(IBChain::my_asset_balance((9, asset_id)), 15);
+            assert_eq!(ibchain_events().last(), Some(&RawEvent::DeliveredToFallback(1, 3, 9, asset_id, 15)));
+
+            // sendasset は引き続き失敗する
+            assert_noop!(IBChain::sendasset(Origin::signed(1), 3, asset_id, 10), "Recipient opted out of this asset");
+        });
+    }
+
+    #[test]
+    fn sendasset_rejects_recipient_overflow() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), u64::max_value(), true));
+            let asset_id = last_issued_asset(1);
+            assert_ok!(IBChain::sendasset(Origin::signed(1), 2, asset_id, u64::max_value() - 20));
+            // 受信者の所有量を上限近くにする (発行済量の範囲を超えるのはテストのため)
+            <MyAssetBalances<Test>>::insert((2, asset_id), u64::max_value() - 5);
+
+            assert_noop!(IBChain::sendasset(Origin::signed(1), 2, asset_id, 10), "Overflow adding (to)'s asset");
+            assert_eq!(IBChain::my_asset_balance((1, asset_id)), 20);
+            assert_eq!(IBChain::my_asset_balance((2, asset_id)), u64::max_value() - 5);
+        });
+    }
+
+    #[test]
+    fn burn_on_transfer_reduces_supply() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 1_000, true));
+            let asset_id = last_issued_asset(1);
+
+            assert_noop!(IBChain::set_burn_bps(Origin::signed(2), asset_id, 100), "You do not own this asset");
+            assert_noop!(IBChain::set_burn_bps(Origin::signed(1), asset_id, 10_001), "Burn share exceeds 100%");
+            // 2%
+            assert_ok!(IBChain::set_burn_bps(Origin::signed(1), asset_id, 200));
+
+            assert_ok!(IBChain::sendasset(Origin::signed(1), 2, asset_id, 500));
+            assert_eq!(IBChain::my_asset_balance((1, asset_id)), 500);
+            assert_eq!(IBChain::my_asset_balance((2, asset_id)), 490);
+            assert_eq!(IBChain::total_issued_asset(asset_id), 990);
+            assert!(IBChain::verify_asset(asset_id));
+        });
+    }
+
+    #[test]
+    fn transfer_deductions_are_capped_at_the_amount() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 1_000, true));
+            let asset_id = last_issued_asset(1);
+            assert_ok!(IBChain::set_community_fund(Origin::signed(1), asset_id, Some(9), 1_000));
+            assert_ok!(IBChain::set_burn_bps(Origin::signed(1), asset_id, 500));
+
+            // 分配 10% + 焼却 5%
+            assert_ok!(IBChain::sendasset(Origin::signed(1), 2, asset_id, 100));
+            assert_eq!(IBChain::my_asset_balance((2, asset_id)), 85);
+            assert_eq!(IBChain::my_asset_balance((9, asset_id)), 10);
+            assert_eq!(IBChain::total_issued_asset(asset_id), 995);
+
+            // 分配 60% + 焼却 50% は送信量を超える
+            assert_ok!(IBChain::set_community_fund(Origin::signed(1), asset_id, Some(9), 6_000));
+            assert_ok!(IBChain::set_burn_bps(Origin::signed(1), asset_id, 5_000));
+            assert_noop!(IBChain::sendasset(Origin::signed(1), 2, asset_id, 100), "Transfer deductions exceed the amount");
+        });
+    }
+
+    #[test]
+    fn transfer_deductions_apply_to_every_user_path() {
+        // 経路ごとに 1 から 2 へ 100 送り、(1, 2, 基金 9 の所有量, 発行済量) を比べる
+        let run = |path: u8| {
+            with_externalities(&mut new_test_ext(), || {
+                assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 1_000, true));
+                let asset_id = last_issued_asset(1);
+                assert_ok!(IBChain::set_community_fund(Origin::signed(1), asset_id, Some(9), 1_000));
+                assert_ok!(IBChain::set_burn_bps(Origin::signed(1), asset_id, 500));
+                match path {
+                    0 => assert_ok!(IBChain::sendasset(Origin::signed(1), 2, asset_id, 100)),
+                    1 => assert_ok!(IBChain::send_asset_batch(Origin::signed(1), asset_id, vec![(2, 100)], false)),
+                    2 => {
+                        assert_ok!(IBChain::approve(Origin::signed(1), 3, asset_id, 100));
+                        assert_ok!(IBChain::transfer_from(Origin::signed(3), asset_id, 1, 2, 100));
+                    }
+                    _ => {
+                        assert_ok!(IBChain::propose_transfer(Origin::signed(1), 2, asset_id, 100));
+                        let transfer_id = IBChain::escrows_of(&2)[0];
+                        assert_eq!(IBChain::pending_transfer(transfer_id).unwrap().qty, 85);
+                        assert_ok!(IBChain::accept_transfer(Origin::signed(2), transfer_id));
+                    }
+                }
+                assert!(IBChain::verify_asset(asset_id));
+                (
+                    [1u64, 2, 9].iter().map(|who| IBChain::my_asset_balance((*who, asset_id))).collect::<Vec<_>>(),
+                    IBChain::total_issued_asset(asset_id),
+                )
+            })
+        };
+
+        for path in 0..4 {
+            assert_eq!(run(path), (vec![900, 85, 10], 995));
+        }
+
+        // 全資産の移動でも差し引く
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 1_000, true));
+            let asset_id = last_issued_asset(1);
+            assert_ok!(IBChain::set_community_fund(Origin::signed(1), asset_id, Some(9), 1_000));
+            assert_ok!(IBChain::set_burn_bps(Origin::signed(1), asset_id, 500));
+
+            assert_ok!(IBChain::exit_all(Origin::signed(1), 2));
+            assert_eq!(IBChain::my_asset_balance((1, asset_id)), 0);
+            assert_eq!(IBChain::my_asset_balance((2, asset_id)), 850);
+            assert_eq!(IBChain::my_asset_balance((9, asset_id)), 100);
+            assert_eq!(IBChain::total_issued_asset(asset_id), 950);
+        });
+    }
+
+    #[test]
+    fn sending_to_yourself_takes_no_deductions() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 1_000, true));
+            let asset_id = last_issued_asset(1);
+            assert_ok!(IBChain::set_community_fund(Origin::signed(1), asset_id, Some(9), 1_000));
+            assert_ok!(IBChain::set_burn_bps(Origin::signed(1), asset_id, 500));
+
+            assert_ok!(IBChain::sendasset(Origin::signed(1), 1, asset_id, 100));
+            assert_ok!(IBChain::send_asset_batch(Origin::signed(1), asset_id, vec![(1, 100)], false));
+            assert_eq!(IBChain::my_asset_balance((1, asset_id)), 1_000);
+            assert_eq!(IBChain::my_asset_balance((9, asset_id)), 0);
+            assert_eq!(IBChain::total_issued_asset(asset_id), 1_000);
+        });
+    }
+
+    #[test]
+    fn holdings_diff_covers_both_accounts() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"shared".to_vec(), 1_000, true));
+            let shared = last_issued_asset(1);
+            assert_ok!(IBChain::issue(Origin::signed(1), b"only_a".to_vec(), 500, true));
+            let only_a = last_issued_asset(1);
+            assert_ok!(IBChain::issue(Origin::signed(2), b"only_b".to_vec(), 300, true));
+            let only_b = last_issued_asset(2);
+            assert_ok!(IBChain::sendasset(Origin::signed(1), 2, shared, 400));
+
+            assert_eq!(IBChain::holdings_diff(&1, &2), vec![
+                (shared, 600, 400),
+                (only_a, 500, 0),
+                (only_b, 0, 300),
+            ]);
+            assert_eq!(IBChain::holdings_diff(&2, &1), vec![
+                (only_b, 300, 0),
+                (shared, 400, 600),
+                (only_a, 0, 500),
+            ]);
+            assert_eq!(IBChain::holdings_diff(&3, &4), vec![]);
+        });
+    }
+
+    #[test]
+    fn account_tx_nonce_counts_each_operation() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_eq!(IBChain::account_tx_nonce(1), 0);
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+            assert_eq!(IBChain::account_tx_nonce(1), 1);
+
+            assert_ok!(IBChain::issuemore(Origin::signed(1), asset_id, 10));
+            assert_eq!(ibchain_events().last(), Some(&RawEvent::IssuedMore(1, asset_id, 10, 1, 1)));
+
+            assert_ok!(IBChain::sendasset(Origin::signed(1), 2, asset_id, 30));
+            assert_eq!(ibchain_events().last(), Some(&RawEvent::SentAsset(1, 2, asset_id, 30, 2, 2)));
+            assert_eq!(IBChain::account_tx_nonce(1), 3);
+            // 受信しても受信者の番号は進まない
+            assert_eq!(IBChain::account_tx_nonce(2), 0);
+
+            assert_ok!(IBChain::burn(Origin::signed(2), asset_id, 5));
+            assert_eq!(ibchain_events().last(), Some(&RawEvent::Burned(2, asset_id, 5, 3, 0)));
+            assert_eq!(IBChain::account_tx_nonce(2), 1);
+
+            // 失敗した操作では進まない
+            assert_noop!(IBChain::sendasset(Origin::signed(2), 3, asset_id, 1_000),
+                "Your asset is less than you want to send the amount.");
+            assert_eq!(IBChain::account_tx_nonce(2), 1);
+        });
+    }
+
+    #[test]
+    fn import_snapshot_checks_new_accounts_before_writing() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+            <MyAssetsCount<Test>>::insert(9, u64::max_value());
+
+            // 2 件目の新しいアカウントで失敗しても、1 件目は書き換わらない
+            assert_noop!(IBChain::import_snapshot(Origin::ROOT, asset_id, vec![(2, 50), (9, 50)]),
+                "Overflow adding a new My Asset to total supply");
+        });
+    }
+
+    #[test]
+    fn import_snapshot_round_trips() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+            assert_ok!(IBChain::send_asset_batch(Origin::signed(1), asset_id, vec![(2, 30), (3, 20)], false));
+            assert_ok!(IBChain::sendasset(Origin::signed(3), 2, asset_id, 20));
+
+            let snapshot = IBChain::snapshot(asset_id);
+            assert_eq!(snapshot, vec![(1, 50), (2, 50)]);
+
+            assert!(IBChain::import_snapshot(Origin::signed(1), asset_id, snapshot.clone()).is_err());
+            assert_noop!(IBChain::import_snapshot(Origin::ROOT, BlakeTwo256::hash_of(&1u32), snapshot.clone()),
+                "This asset does not exist");
+            assert_noop!(IBChain::import_snapshot(Origin::ROOT, asset_id, vec![(2, 1), (2, 1)]),
+                "Duplicate account in snapshot");
+
+            // 空のスナップショットで消去
+            assert_ok!(IBChain::import_snapshot(Origin::ROOT, asset_id, vec![]));
+            assert_eq!(IBChain::snapshot(asset_id), vec![]);
+            assert_eq!(IBChain::total_issued_asset(asset_id), 0);
+            assert_eq!(IBChain::holder_count(asset_id), 0);
+
+            assert_ok!(IBChain::import_snapshot(Origin::ROOT, asset_id, snapshot.clone()));
+            assert_eq!(IBChain::snapshot(asset_id), snapshot);
+            assert_eq!(IBChain::my_asset_balance((1, asset_id)), 50);
+            assert_eq!(IBChain::my_asset_balance((2, asset_id)), 50);
+            assert_eq!(IBChain::my_asset_balance((3, asset_id)), 0);
+            assert_eq!(IBChain::total_issued_asset(asset_id), 100);
+            assert_eq!(IBChain::holder_count(asset_id), 2);
+            assert!(IBChain::verify_asset(asset_id));
+
+            // 新しいアカウントも保有者として登録される
+            assert_ok!(IBChain::import_snapshot(Origin::ROOT, asset_id, vec![(1, 10), (7, 5)]));
+            assert_eq!(IBChain::my_asset_balance((2, asset_id)), 0);
+            assert_eq!(IBChain::my_assets(&7), vec![(asset_id, 5)]);
+            assert_eq!(IBChain::total_issued_asset(asset_id), 15);
+            assert!(IBChain::verify_asset(asset_id));
+        });
+    }
+
+    #[test]
+    fn try_asset_distinguishes_missing_assets() {
+        with_externalities(&mut new_test_ext(), || {
+            let unknown = BlakeTwo256::hash_of(&1u32);
+            assert_eq!(IBChain::try_asset(unknown), None);
+            assert_eq!(IBChain::asset(unknown), Asset::default());
+
+            assert_noop!(IBChain::sendasset(Origin::signed(1), 2, unknown, 1), "This asset does not exist");
+            assert_noop!(IBChain::issuemore(Origin::signed(1), unknown, 1), "This asset does not exist");
+
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+            assert_eq!(IBChain::try_asset(asset_id), Some(IBChain::asset(asset_id)));
+        });
+    }
+
+    #[test]
+    fn faucet_is_rate_limited_per_account() {
+        with_externalities(&mut new_test_ext(), || {
+            System::set_block_number(1);
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+
+            assert_noop!(IBChain::faucet(Origin::signed(2), asset_id), "Faucet is not enabled for this asset");
+            assert_noop!(IBChain::set_faucet(Origin::signed(2), asset_id, 10), "You do not own this asset");
+            assert_ok!(IBChain::set_faucet(Origin::signed(1), asset_id, 10));
+            assert!(IBChain::set_faucet_cooldown(Origin::signed(1), 5).is_err());
+            assert_ok!(IBChain::set_faucet_cooldown(Origin::ROOT, 5));
+
+            let seq = IBChain::event_seq();
+            assert_ok!(IBChain::faucet(Origin::signed(2), asset_id));
+            assert_eq!(IBChain::my_asset_balance((2, asset_id)), 10);
+            assert_eq!(IBChain::total_issued_asset(asset_id), 110);
+            let events = ibchain_events();
+            assert_eq!(&events[events.len() - 2..], &[
+                RawEvent::IssuedMore(2, asset_id, 10, seq, 0),
+                RawEvent::FaucetClaimed(2, asset_id, 10),
+            ]);
+            assert_eq!(IBChain::event_seq(), seq + 1);
+
+            System::set_block_number(5);
+            assert_noop!(IBChain::faucet(Origin::signed(2), asset_id), "Faucet cooldown has not passed");
+            // 他のアカウントは影響を受けない
+            assert_ok!(IBChain::faucet(Origin::signed(3), asset_id));
+
+            System::set_block_number(6);
+            assert_ok!(IBChain::faucet(Origin::signed(2), asset_id));
+            assert_eq!(IBChain::my_asset_balance((2, asset_id)), 20);
+            assert_eq!(IBChain::total_issued_asset(asset_id), 130);
+            assert!(IBChain::verify_asset(asset_id));
+        });
+    }
+
+    #[test]
+    fn faucet_requires_open_asset() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, false));
+            let asset_id = last_issued_asset(1);
+
+            assert_noop!(IBChain::set_faucet(Origin::signed(1), asset_id, 10), "You can not issue more");
+            assert_noop!(IBChain::faucet(Origin::signed(2), BlakeTwo256::hash_of(&1u32)), "This asset does not exist");
+        });
+    }
+
+    #[test]
+    fn faucet_checks_recipient_limits_before_minting() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+            assert_ok!(IBChain::set_faucet(Origin::signed(1), asset_id, 10));
+
+            assert_ok!(IBChain::set_requires_allowlist(Origin::signed(1), asset_id, true));
+            assert_noop!(IBChain::faucet(Origin::signed(2), asset_id), "Recipient is not allowlisted for this asset");
+            assert_ok!(IBChain::set_allowlisted(Origin::signed(1), asset_id, 2, true));
+            assert_ok!(IBChain::set_allowlisted(Origin::signed(1), asset_id, 3, true));
+
+            assert_ok!(IBChain::set_max_mint_per_call(Origin::signed(1), asset_id, 5));
+            assert_noop!(IBChain::faucet(Origin::signed(2), asset_id), "Mint exceeds per-call cap");
+            assert_ok!(IBChain::set_max_mint_per_call(Origin::signed(1), asset_id, 0));
+
+            assert_ok!(IBChain::set_max_holders(Origin::signed(1), asset_id, 2));
+            assert_ok!(IBChain::faucet(Origin::signed(2), asset_id));
+            assert_noop!(IBChain::faucet(Origin::signed(3), asset_id), "Max holders reached");
+            assert_eq!(IBChain::total_issued_asset(asset_id), 110);
+        });
+    }
+
+    #[test]
+    fn issue_frozen_blocks_transfers_until_unfrozen() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue_frozen(Origin::signed(1), b"launch".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+            assert!(IBChain::frozen(asset_id));
+            assert_eq!(IBChain::my_asset_balance((1, asset_id)), 100);
+
+            assert_noop!(IBChain::sendasset(Origin::signed(1), 2, asset_id, 10), "Asset is frozen");
+            assert_noop!(IBChain::send_asset_batch(Origin::signed(1), asset_id, vec![(2, 10)], false), "Asset is frozen");
+            assert_ok!(IBChain::approve(Origin::signed(1), 3, asset_id, 10));
+            assert_noop!(IBChain::transfer_from(Origin::signed(3), asset_id, 1, 2, 10), "Asset is frozen");
+            // 発行者は凍結中も追加発行できる
+            assert_ok!(IBChain::issuemore(Origin::signed(1), asset_id, 10));
+
+            assert_noop!(IBChain::unfreeze(Origin::signed(2), asset_id), "You do not own this asset");
+            assert_ok!(IBChain::unfreeze(Origin::signed(1), asset_id));
+            assert!(!IBChain::frozen(asset_id));
+            assert_ok!(IBChain::sendasset(Origin::signed(1), 2, asset_id, 10));
+            assert_eq!(IBChain::my_asset_balance((2, asset_id)), 10);
+
+            assert_ok!(IBChain::freeze(Origin::signed(1), asset_id));
+            assert_noop!(IBChain::sendasset(Origin::signed(2), 1, asset_id, 5), "Asset is frozen");
+        });
+    }
+
+    #[test]
+    fn storage_footprint_grows_with_holders() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_eq!(IBChain::storage_footprint(BlakeTwo256::hash_of(&1u32)), 0);
+
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+            let issued = IBChain::storage_footprint(asset_id);
+            assert!(issued > 0);
+
+            assert_ok!(IBChain::sendasset(Origin::signed(1), 2, asset_id, 10));
+            let two_holders = IBChain::storage_footprint(asset_id);
+            assert!(two_holders > issued);
+
+            // 既存の保有者への送信ではエントリが増えない
+            assert_ok!(IBChain::sendasset(Origin::signed(1), 2, asset_id, 10));
+            assert_eq!(IBChain::storage_footprint(asset_id), two_holders);
+
+            // 保有者 1 人あたりの増分は一定
+            assert_ok!(IBChain::sendasset(Origin::signed(1), 3, asset_id, 10));
+            assert_eq!(IBChain::storage_footprint(asset_id) - two_holders, two_holders - issued);
+        });
+    }
+
+    #[test]
+    fn rebuild_account_index_restores_positions() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"a".to_vec(), 100, true));
+            let a = last_issued_asset(1);
+            assert_ok!(IBChain::issue(Origin::signed(1), b"b".to_vec(), 100, true));
+            let b = last_issued_asset(1);
+
+            // 過去の不具合を想定して壊す
+            <MyAssetsIndex<Test>>::insert((1, a), 7);
+            <MyAssetsIndex<Test>>::remove((1, b));
+
+            assert!(IBChain::rebuild_account_index(Origin::signed(1), 1).is_err());
+            assert_ok!(IBChain::rebuild_account_index(Origin::ROOT, 1));
+
+            assert_eq!(<MyAssetsIndex<Test>>::get((1, a)), 0);
+            assert_eq!(<MyAssetsIndex<Test>>::get((1, b)), 1);
+            assert!(<MyAssetsIndex<Test>>::exists((1, b)));
+            // 修復後は通常どおり送信できる
+            assert_ok!(IBChain::sendasset(Origin::signed(1), 2, b, 10));
+            assert_eq!(IBChain::my_asset_count(&1), 2);
+        });
+    }
+
+    #[test]
+    fn acknowledged_expiring_transfer_is_delivered() {
+        with_externalities(&mut new_test_ext(), || {
+            System::set_block_number(1);
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+
+            assert_noop!(IBChain::propose_expiring_transfer(Origin::signed(1), 2, asset_id, 30, 0), "Expiry must be in the future");
+            assert_ok!(IBChain::propose_expiring_transfer(Origin::signed(1), 2, asset_id, 30, 5));
+            let transfer_id = last_pending_transfer();
+            assert_eq!(IBChain::transfer_expiry(transfer_id), Some(6));
+            assert_eq!(IBChain::expiring_transfers(6), vec![transfer_id]);
+            assert_eq!(IBChain::my_asset_balance((1, asset_id)), 70);
+
+            System::set_block_number(6);
+            assert_noop!(IBChain::acknowledge_receipt(Origin::signed(3), transfer_id), "You are not the recipient of this transfer");
+            assert_ok!(IBChain::acknowledge_receipt(Origin::signed(2), transfer_id));
+            assert_eq!(IBChain::my_asset_balance((2, asset_id)), 30);
+            assert_eq!(IBChain::pending_transfer(transfer_id), None);
+            assert_eq!(IBChain::transfer_expiry(transfer_id), None);
+            assert_eq!(IBChain::expiring_transfers(6), vec![]);
+
+            // 期限のない送信は受信確認の対象外
+            assert_ok!(IBChain::propose_transfer(Origin::signed(1), 2, asset_id, 10));
+            assert_noop!(IBChain::acknowledge_receipt(Origin::signed(2), last_pending_transfer()), "This transfer does not expire");
+        });
+    }
+
+    #[test]
+    fn unacknowledged_expiring_transfer_is_refunded() {
+        with_externalities(&mut new_test_ext(), || {
+            System::set_block_number(1);
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+            assert_ok!(IBChain::propose_expiring_transfer(Origin::signed(1), 2, asset_id, 30, 2));
+            let transfer_id = last_pending_transfer();
+
+            // 期限前のブロックでは何もしない
+            <IBChain as OnFinalise<u64>>::on_finalise(2);
+            assert!(IBChain::pending_transfer(transfer_id).is_some());
+
+            <IBChain as OnFinalise<u64>>::on_finalise(3);
+            assert_eq!(IBChain::pending_transfer(transfer_id), None);
+            assert_eq!(IBChain::my_asset_balance((1, asset_id)), 100);
+            assert_eq!(IBChain::my_asset_balance((2, asset_id)), 0);
+            assert_eq!(IBChain::escrows_of(&1), vec![]);
+            assert_eq!(ibchain_events().last(), Some(&RawEvent::TransferExpired(transfer_id)));
+
+            System::set_block_number(4);
+            assert_noop!(IBChain::acknowledge_receipt(Origin::signed(2), transfer_id), "This pending transfer does not exist");
+        });
+    }
+
+    #[test]
+    fn try_total_issued_distinguishes_burned_from_missing() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+            assert_eq!(IBChain::try_total_issued(asset_id), Some(100));
+
+            assert_ok!(IBChain::burn(Origin::signed(1), asset_id, 100));
+            assert_eq!(IBChain::total_issued_asset(asset_id), 0);
+            assert_eq!(IBChain::try_total_issued(asset_id), Some(0));
+
+            let unknown = BlakeTwo256::hash_of(&1u32);
+            assert_eq!(IBChain::total_issued_asset(unknown), 0);
+            assert_eq!(IBChain::try_total_issued(unknown), None);
+        });
+    }
+
+    #[test]
+    fn event_verbosity_controls_emitted_events() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_eq!(IBChain::event_verbosity(), EVENTS_DETAILED);
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+
+            assert!(IBChain::set_event_verbosity(Origin::signed(1), EVENTS_OFF).is_err());
+            assert_noop!(IBChain::set_event_verbosity(Origin::ROOT, 3), "Unknown event verbosity");
+
+            assert_ok!(IBChain::set_event_verbosity(Origin::ROOT, EVENTS_OFF));
+            let before = ibchain_events().len();
+            let seq = IBChain::event_seq();
+            assert_ok!(IBChain::sendasset(Origin::signed(1), 2, asset_id, 30));
+            assert_ok!(IBChain::approve(Origin::signed(1), 3, asset_id, 10));
+            assert_ok!(IBChain::burn(Origin::signed(2), asset_id, 5));
+            assert_eq!(ibchain_events().len(), before);
+            // 所有量とイベント番号は変わる
+            assert_eq!(IBChain::my_asset_balance((2, asset_id)), 25);
+            assert_eq!(IBChain::total_issued_asset(asset_id), 95);
+            assert_eq!(IBChain::event_seq(), seq + 2);
+
+            assert_ok!(IBChain::set_event_verbosity(Origin::ROOT, EVENTS_CORE));
+            assert_ok!(IBChain::approve(Origin::signed(1), 3, asset_id, 20));
+            assert_eq!(ibchain_events().len(), before);
+            assert_ok!(IBChain::sendasset(Origin::signed(1), 2, asset_id, 5));
+            assert_eq!(ibchain_events().len(), before + 1);
+        });
+    }
+
+    #[cfg(feature = "runtime-benchmarks")]
+    #[test]
+    fn benchmark_setups_dispatch() {
+        use crate::benchmarking::*;
+
+        for &name_len in ISSUE_NAME_LENS.iter() {
+            with_externalities(&mut new_test_ext(), || {
+                assert_ok!(bench_issue::<Test>(1, setup_issue(name_len)));
+                assert_eq!(IBChain::asset(last_issued_asset(1)).name.len(), name_len);
+            });
+        }
+        for &recipient in SENDASSET_RECIPIENTS.iter() {
+            with_externalities(&mut new_test_ext(), || {
+                let asset_id = setup_sendasset::<Test>(1, 2, recipient).unwrap();
+                let registered = IBChain::my_asset_count(&2);
+                assert_eq!(registered, if recipient == Recipient::New { 0 } else { 1 });
+
+                assert_ok!(bench_sendasset::<Test>(1, 2, asset_id));
+                assert_eq!(IBChain::my_asset_count(&2), 1);
+            });
+        }
+    }
+
+    #[test]
+    fn total_volume_saturates_instead_of_failing() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+            <TotalVolume<Test>>::insert(asset_id, u128::max_value() - 5);
+
+            assert_ok!(IBChain::sendasset(Origin::signed(1), 2, asset_id, 10));
+            assert_eq!(IBChain::my_asset_balance((2, asset_id)), 10);
+            assert_eq!(IBChain::total_volume(asset_id), u128::max_value());
+
+            assert_ok!(IBChain::sendasset(Origin::signed(2), 3, asset_id, 1));
+            assert_eq!(IBChain::total_volume(asset_id), u128::max_value());
+        });
+    }
+
+    #[test]
+    fn non_burnable_asset_rejects_burns() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue_non_burnable(Origin::signed(1), b"fixed".to_vec(), 100, false));
+            let fixed = last_issued_asset(1);
+            assert!(!IBChain::is_burnable(fixed));
+
+            assert_noop!(IBChain::burn(Origin::signed(1), fixed, 10), "Asset is not burnable");
+            assert_ok!(IBChain::approve(Origin::signed(1), 2, fixed, 10));
+            assert_noop!(IBChain::burn_from(Origin::signed(2), 1, fixed, 10), "Asset is not burnable");
+            assert_noop!(IBChain::set_burn_bps(Origin::signed(1), fixed, 100), "Asset is not burnable");
+            assert_ok!(IBChain::set_burn_bps(Origin::signed(1), fixed, 0));
+            assert_eq!(IBChain::total_issued_asset(fixed), 100);
+
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, false));
+            let coin = last_issued_asset(1);
+            assert!(IBChain::is_burnable(coin));
+            assert_ok!(IBChain::burn(Origin::signed(1), coin, 10));
+            assert_eq!(IBChain::total_issued_asset(coin), 90);
+        });
+    }
+
+    #[test]
+    fn exit_all_moves_every_held_asset() {
+        with_externalities(&mut new_test_ext(), || {
+            let mut assets = vec![];
+            for (i, name) in [b"a", b"b", b"c"].iter().enumerate() {
+                assert_ok!(IBChain::issue(Origin::signed(1), name.to_vec(), 10 * (i as u64 + 1), true));
+                assets.push(last_issued_asset(1));
+            }
+            // 送れない資産と、所有量 0 の資産
+            assert_ok!(IBChain::issue_frozen(Origin::signed(1), b"frozen".to_vec(), 5, true));
+            let frozen = last_issued_asset(1);
+            assert_ok!(IBChain::issue(Origin::signed(3), b"gone".to_vec(), 5, true));
+            let gone = last_issued_asset(3);
+            assert_ok!(IBChain::sendasset(Origin::signed(3), 1, gone, 5));
+            assert_ok!(IBChain::sendasset(Origin::signed(1), 3, gone, 5));
+
+            assert_noop!(IBChain::exit_all(Origin::signed(1), 1), "You can not exit to yourself");
+            assert_ok!(IBChain::exit_all(Origin::signed(1), 2));
+
+            for (i, asset_id) in assets.iter().enumerate() {
+                assert_eq!(IBChain::my_asset_balance((1, *asset_id)), 0);
+                assert_eq!(IBChain::my_asset_balance((2, *asset_id)), 10 * (i as u64 + 1));
+            }
+            assert_eq!(IBChain::my_assets(&2).len(), 3);
+            assert_eq!(IBChain::my_asset_balance((1, frozen)), 5);
+            assert_eq!(IBChain::my_asset_balance((2, gone)), 0);
+
+            // 所有量が 0 になった資産 (と元から 0 の資産) の登録は取り除かれ、送れなかった資産だけが残る
+            assert_eq!(IBChain::my_asset_count(&1), 1);
+            assert_eq!(IBChain::my_asset_by_index((1, 0)), frozen);
+            assert!(!<MyAssetsIndex<Test>>::exists((1, assets[0])));
+            assert!(!<MyAssetsIndex<Test>>::exists((1, gone)));
+            assert_eq!(IBChain::asset_holders_count(assets[0]), 1);
+            assert_eq!(IBChain::asset_holders_count(gone), 1);
+            for asset_id in assets.iter() {
+                assert!(IBChain::verify_asset(*asset_id));
+            }
+
+            // 再び受け取ると、新しく登録される
+            assert_ok!(IBChain::sendasset(Origin::signed(2), 1, assets[0], 5));
+            assert_eq!(IBChain::my_asset_count(&1), 2);
+            assert_eq!(IBChain::asset_holders_count(assets[0]), 2);
+            assert!(IBChain::verify_asset(assets[0]));
+
+            // 送れるものが残っていなければ失敗する
+            assert_noop!(IBChain::exit_all(Origin::signed(1), 2), "No assets to move");
+        });
+    }
+
+    #[test]
+    fn strict_holder_mode_rejects_a_second_new_holder() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 1000, true));
+            let asset_id = last_issued_asset(1);
+            // 送信のたびに基金 9 へも分配される
+            assert_ok!(IBChain::set_community_fund(Origin::signed(1), asset_id, Some(9), 1000));
+
+            assert!(IBChain::set_strict_holder_mode(Origin::signed(1), true).is_err());
+            assert_ok!(IBChain::set_strict_holder_mode(Origin::ROOT, true));
+
+            // 受信者 2 と基金 9 が同時に新しい保有者になる
+            assert_noop!(IBChain::sendasset(Origin::signed(1), 2, asset_id, 100), "Too many new holders in one transfer");
+
+            // 基金が保有者になった後は、新しい保有者は受信者だけ
+            assert_ok!(IBChain::sendasset(Origin::signed(1), 9, asset_id, 100));
+            assert_ok!(IBChain::sendasset(Origin::signed(1), 2, asset_id, 100));
+            assert_eq!(IBChain::my_asset_balance((2, asset_id)), 90);
+
+            // 一括送信は対象外
+            assert_ok!(IBChain::send_asset_batch(Origin::signed(1), asset_id, vec![(3, 10), (4, 10)], false));
+
+            assert_ok!(IBChain::set_strict_holder_mode(Origin::ROOT, false));
+            assert_ok!(IBChain::set_community_fund(Origin::signed(1), asset_id, Some(8), 1000));
+            assert_ok!(IBChain::sendasset(Origin::signed(1), 5, asset_id, 100));
+        });
+    }
+
+    #[test]
+    fn assets_by_open_filters_each_way() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"open-a".to_vec(), 10, true));
+            let open_a = last_issued_asset(1);
+            assert_ok!(IBChain::issue(Origin::signed(1), b"fixed-a".to_vec(), 10, false));
+            let fixed_a = last_issued_asset(1);
+            assert_ok!(IBChain::issue(Origin::signed(2), b"open-b".to_vec(), 10, true));
+            let open_b = last_issued_asset(2);
+            assert_ok!(IBChain::issue(Origin::signed(2), b"fixed-b".to_vec(), 10, false));
+            let fixed_b = last_issued_asset(2);
+
+            assert_eq!(IBChain::assets_by_open(true, 0, 10),
+                vec![(open_a, b"open-a".to_vec()), (open_b, b"open-b".to_vec())]);
+            assert_eq!(IBChain::assets_by_open(false, 0, 10),
+                vec![(fixed_a, b"fixed-a".to_vec()), (fixed_b, b"fixed-b".to_vec())]);
+
+            // ページ分け: 全資産配列の 2 番目から 2 件
+            assert_eq!(IBChain::assets_by_open(false, 2, 2), vec![(fixed_b, b"fixed-b".to_vec())]);
+            assert_eq!(IBChain::assets_by_open(true, 4, 10), vec![]);
+            assert_eq!(IBChain::assets_by_open(true, u64::max_value(), u64::max_value()), vec![]);
+        });
+    }
+
+    #[test]
+    fn asset_config_reflects_each_setting() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_eq!(IBChain::asset_config(BlakeTwo256::hash_of(&1u32)), None);
+
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+            assert_eq!(IBChain::asset_config(asset_id), Some(AssetConfig {
+                open: true,
+                burnable: true,
+                events_enabled: true,
+                ..Default::default()
+            }));
+
+            assert_ok!(IBChain::freeze(Origin::signed(1), asset_id));
+            assert_ok!(IBChain::set_require_memo(Origin::signed(1), asset_id, true));
+            assert_ok!(IBChain::set_requires_allowlist(Origin::signed(1), asset_id, true));
+            assert_ok!(IBChain::set_community_fund(Origin::signed(1), asset_id, Some(9), 250));
+            assert_ok!(IBChain::set_burn_bps(Origin::signed(1), asset_id, 100));
+            assert_ok!(IBChain::set_max_holders(Origin::signed(1), asset_id, 5));
+            assert_ok!(IBChain::set_cooldown(Origin::signed(1), asset_id, 3));
+            assert_ok!(IBChain::set_events_enabled(Origin::signed(1), asset_id, false));
+
+            assert_eq!(IBChain::asset_config(asset_id), Some(AssetConfig {
+                open: true,
+                burnable: true,
+                frozen: true,
+                require_memo: true,
+                requires_allowlist: true,
+                restrict_destinations: false,
+                events_enabled: false,
+                community_fund: Some(9),
+                community_bps: 250,
+                burn_bps: 100,
+                max_holders: 5,
+                max_mint_per_call: 0,
+                cooldown: 3,
+            }));
+        });
+    }
+
+    #[test]
+    fn transfer_restrictions_apply_to_sendasset_and_transfer_from_alike() {
+        // (制限の設定, 期待するエラー)
+        let cases: Vec<(fn(H256), &'static str)> = vec![
+            (|asset_id| assert_ok!(IBChain::freeze(Origin::signed(1), asset_id)), "Asset is frozen"),
+            (|asset_id| {
+                assert_ok!(IBChain::set_cooldown(Origin::signed(1), asset_id, 3));
+                assert_ok!(IBChain::sendasset(Origin::signed(1), 4, asset_id, 1));
+            }, "Transfer cooldown has not passed"),
+            (|asset_id| assert_ok!(IBChain::opt_out(Origin::signed(3), asset_id)), "Recipient opted out of this asset"),
+            (|asset_id| assert_ok!(IBChain::set_requires_allowlist(Origin::signed(1), asset_id, true)),
+                "Recipient is not allowlisted for this asset"),
+            (|asset_id| assert_ok!(IBChain::set_restrict_destinations(Origin::signed(1), asset_id, true)),
+                "Destination is not allowed for this asset"),
+        ];
+        for (restrict, err) in cases {
+            with_externalities(&mut new_test_ext(), || {
+                assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+                let asset_id = last_issued_asset(1);
+                assert_ok!(IBChain::approve(Origin::signed(1), 2, asset_id, 50));
+                restrict(asset_id);
+
+                assert_noop!(IBChain::sendasset(Origin::signed(1), 3, asset_id, 10), err);
+                assert_noop!(IBChain::transfer_from(Origin::signed(2), asset_id, 1, 3, 10), err);
+                assert_noop!(IBChain::send_asset_batch(Origin::signed(1), asset_id, vec![(3, 10)], false), err);
+                assert_noop!(<IBChain as Fungible<u64, H256>>::transfer(&1, &3, asset_id, 10), err);
+            });
+        }
+
+        // モジュール管理アカウントへは、どの経路でも送れない
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+            assert_ok!(IBChain::approve(Origin::signed(1), 2, asset_id, 50));
+            let escrow = IBChain::escrow_account();
+            let err = "Cannot send to a reserved account";
+
+            assert_noop!(IBChain::sendasset(Origin::signed(1), escrow, asset_id, 10), err);
+            assert_noop!(IBChain::transfer_from(Origin::signed(2), asset_id, 1, escrow, 10), err);
+            assert_noop!(IBChain::send_asset_batch(Origin::signed(1), asset_id, vec![(escrow, 10)], false), err);
+            assert_noop!(<IBChain as Fungible<u64, H256>>::transfer(&1, &escrow, asset_id, 10), err);
+        });
+    }
+
+    #[test]
+    fn transfer_restrictions_apply_to_module_account_paths() {
+        // 利用者からモジュール管理アカウントへ: 送信者側の制限
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+            assert_ok!(IBChain::set_cooldown(Origin::signed(1), asset_id, 3));
+            assert_ok!(IBChain::sendasset(Origin::signed(1), 2, asset_id, 20));
+            let err = "Transfer cooldown has not passed";
+
+            assert_noop!(IBChain::lock_for_bridge(Origin::signed(1), asset_id, 10, b"0xdead".to_vec()), err);
+            assert_noop!(IBChain::wrap(Origin::signed(1), asset_id, 10, b"wcoin".to_vec()), err);
+            assert_noop!(IBChain::create_airdrop(Origin::signed(1), asset_id, H256::zero(), 10), err);
+        });
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+            assert_ok!(IBChain::set_requires_allowlist(Origin::signed(1), asset_id, true));
+            assert_ok!(IBChain::set_allowlisted(Origin::signed(1), asset_id, 2, true));
+            assert_ok!(IBChain::sendasset(Origin::signed(1), 2, asset_id, 20));
+            assert_ok!(IBChain::set_allowlisted(Origin::signed(1), asset_id, 2, false));
+            let err = "Sender is not allowlisted for this asset";
+
+            assert_noop!(IBChain::lock_for_bridge(Origin::signed(2), asset_id, 10, b"0xdead".to_vec()), err);
+            assert_noop!(IBChain::wrap(Origin::signed(2), asset_id, 10, b"wcoin".to_vec()), err);
+        });
+
+        // モジュール管理アカウントから利用者へ: 受信者側の制限
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+            assert_ok!(IBChain::lock_for_bridge(Origin::signed(1), asset_id, 30, b"0xdead".to_vec()));
+            let lock_id = last_bridge_lock();
+            assert_ok!(IBChain::set_bridge_authority(Origin::ROOT, 9));
+            assert_ok!(IBChain::opt_out(Origin::signed(3), asset_id));
+
+            assert_noop!(IBChain::release_from_bridge(Origin::signed(9), lock_id, 3, 10),
+                "Recipient opted out of this asset");
+            assert_noop!(IBChain::release_from_bridge(Origin::signed(9), lock_id, IBChain::escrow_account(), 10),
+                "Cannot send to a reserved account");
+        });
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let base = last_issued_asset(1);
+            assert_ok!(IBChain::set_requires_allowlist(Origin::signed(1), base, true));
+            assert_ok!(IBChain::set_allowlisted(Origin::signed(1), base, 2, true));
+            assert_ok!(IBChain::sendasset(Origin::signed(1), 2, base, 20));
+            assert_ok!(IBChain::wrap(Origin::signed(2), base, 10, b"wcoin".to_vec()));
+            let wrapped = last_issued_asset(2);
+            assert_ok!(IBChain::set_allowlisted(Origin::signed(1), base, 2, false));
+
+            assert_noop!(IBChain::unwrap(Origin::signed(2), wrapped, 10), "Recipient is not allowlisted for this asset");
+        });
+        with_externalities(&mut new_test_ext(), || {
+            let (asset_id, airdrop_id, leaves) = setup_airdrop();
+            assert_ok!(IBChain::opt_out(Origin::signed(2), asset_id));
+
+            assert_noop!(IBChain::claim_airdrop(Origin::signed(2), airdrop_id, 20, vec![leaves[0], leaves[2]]),
+                "Recipient opted out of this asset");
+        });
+    }
+
+    #[test]
+    fn atomic_swap_signed_exchanges_both_legs() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"gold".to_vec(), 100, true));
+            let gold = last_issued_asset(1);
+            assert_ok!(IBChain::issue(Origin::signed(2), b"silver".to_vec(), 100, true));
+            let silver = last_issued_asset(2);
+
+            // 2 が「1 の金 10 と 2 の銀 30 の交換」に署名する
+            let sig = |nonce| TestSignature(2, IBChain::swap_payload(&1, &2, gold, 10, silver, 30, nonce));
+
+            assert_ok!(IBChain::atomic_swap_signed(Origin::signed(1), 2, sig(0), gold, 10, silver, 30, 0));
+            assert_eq!(IBChain::my_asset_balance((1, gold)), 90);
+            assert_eq!(IBChain::my_asset_balance((2, gold)), 10);
+            assert_eq!(IBChain::my_asset_balance((1, silver)), 30);
+            assert_eq!(IBChain::my_asset_balance((2, silver)), 70);
+            assert_eq!(IBChain::swap_nonce((2, 1)), 1);
+
+            // 同じ署名の再利用
+            assert_noop!(IBChain::atomic_swap_signed(Origin::signed(1), 2, sig(0), gold, 10, silver, 30, 0),
+                "Invalid swap nonce");
+            assert_noop!(IBChain::atomic_swap_signed(Origin::signed(1), 2, sig(0), gold, 10, silver, 30, 1),
+                "Invalid counterparty signature");
+            // 署名と異なる量・署名者
+            assert_noop!(IBChain::atomic_swap_signed(Origin::signed(1), 2, sig(1), gold, 10, silver, 31, 1),
+                "Invalid counterparty signature");
+            let forged = TestSignature(3, IBChain::swap_payload(&1, &2, gold, 10, silver, 30, 1));
+            assert_noop!(IBChain::atomic_swap_signed(Origin::signed(1), 2, forged, gold, 10, silver, 30, 1),
+                "Invalid counterparty signature");
+            // 他のアカウントは 2 の署名を使えない
+            assert_noop!(IBChain::atomic_swap_signed(Origin::signed(3), 2, sig(0), gold, 10, silver, 30, 0),
+                "Invalid counterparty signature");
+
+            // 相手側が送れない場合は、どちらも送られない
+            let sig = TestSignature(2, IBChain::swap_payload(&1, &2, gold, 10, silver, 71, 1));
+            assert_noop!(IBChain::atomic_swap_signed(Origin::signed(1), 2, sig, gold, 10, silver, 71, 1),
+                "Your asset is less than you want to send the amount.");
+        });
+    }
+
+    #[test]
+    fn remaining_mintable_stops_at_the_u64_ceiling() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"open".to_vec(), 100, true));
+            let open = last_issued_asset(1);
+            assert_ok!(IBChain::issue(Origin::signed(1), b"closed".to_vec(), 100, false));
+            let closed = last_issued_asset(1);
+
+            assert_eq!(IBChain::remaining_mintable(closed), 0);
+            assert_eq!(IBChain::remaining_mintable(BlakeTwo256::hash_of(&1u32)), 0);
+            assert_eq!(IBChain::remaining_mintable(open), u64::max_value() - 100);
+
+            // 焼却した分はまた発行できる
+            assert_ok!(IBChain::burn(Origin::signed(1), open, 40));
+            assert_eq!(IBChain::remaining_mintable(open), u64::max_value() - 60);
+
+            // 上限まで発行すると、open のままでもそれ以上は発行できない
+            assert_ok!(IBChain::issuemore(Origin::signed(1), open, u64::max_value() - 60));
+            assert_eq!(IBChain::remaining_mintable(open), 0);
+            assert_noop!(IBChain::issuemore(Origin::signed(1), open, 1), "Overflow adding a new Asset");
+            assert!(IBChain::asset(open).open);
+        });
+    }
+
+    #[test]
+    fn owner_rotation_applies_after_the_delay() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+            assert_ok!(IBChain::set_owner_rotation_delay(Origin::ROOT, 10));
+            System::set_block_number(5);
+
+            assert_noop!(IBChain::begin_owner_rotation(Origin::signed(2), asset_id, 2), "You do not own this asset");
+            assert_ok!(IBChain::begin_owner_rotation(Origin::signed(1), asset_id, 2));
+            assert_eq!(IBChain::owner_rotation(asset_id), Some((2, 15)));
+
+            System::set_block_number(14);
+            assert_noop!(IBChain::finalize_owner_rotation(Origin::signed(2), asset_id), "Owner rotation is still timelocked");
+            System::set_block_number(15);
+            assert_noop!(IBChain::finalize_owner_rotation(Origin::signed(3), asset_id), "You are not a party to this rotation");
+            assert_ok!(IBChain::finalize_owner_rotation(Origin::signed(2), asset_id));
+
+            assert_eq!(IBChain::owner_of(asset_id), Some(2));
+            assert_eq!(IBChain::owned_assets(&1), vec![]);
+            assert_eq!(IBChain::owned_assets(&2), vec![asset_id]);
+            assert_eq!(IBChain::owner_rotation(asset_id), None);
+            // 所有量は変わらない
+            assert_eq!(IBChain::my_asset_balance((1, asset_id)), 100);
+        });
+    }
+
+    #[test]
+    fn owner_rotation_can_be_cancelled_before_it_applies() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+            assert_ok!(IBChain::set_owner_rotation_delay(Origin::ROOT, 10));
+
+            assert_ok!(IBChain::begin_owner_rotation(Origin::signed(1), asset_id, 2));
+            assert_noop!(IBChain::cancel_owner_rotation(Origin::signed(2), asset_id), "You do not own this asset");
+            assert_ok!(IBChain::cancel_owner_rotation(Origin::signed(1), asset_id));
+            assert_noop!(IBChain::cancel_owner_rotation(Origin::signed(1), asset_id), "No owner rotation for this asset");
+
+            System::set_block_number(20);
+            assert_noop!(IBChain::finalize_owner_rotation(Origin::signed(2), asset_id), "No owner rotation for this asset");
+            assert_eq!(IBChain::owner_of(asset_id), Some(1));
+
+            // 即時の所有者変更で所有者が変わると、前の所有者が始めた変更は消える
+            assert_ok!(IBChain::begin_owner_rotation(Origin::signed(1), asset_id, 3));
+            assert_ok!(IBChain::propose_new_owner(Origin::signed(1), asset_id, 2));
+            assert_ok!(IBChain::accept_ownership(Origin::signed(2), asset_id));
+            assert_eq!(IBChain::owner_rotation(asset_id), None);
+        });
+    }
+
+    #[test]
+    fn transfer_restrictions_sets_a_bit_per_active_restriction() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_eq!(IBChain::transfer_restrictions(&1, &2, BlakeTwo256::hash_of(&1u32)), RESTRICTION_UNKNOWN_ASSET);
+
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+            assert_eq!(IBChain::transfer_restrictions(&1, &2, asset_id), 0);
+            assert_eq!(IBChain::transfer_restrictions(&1, &IBChain::escrow_account(), asset_id),
+                RESTRICTION_RESERVED_RECIPIENT);
+
+            // 凍結と受け取り拒否
+            assert_ok!(IBChain::freeze(Origin::signed(1), asset_id));
+            assert_ok!(IBChain::opt_out(Origin::signed(2), asset_id));
+            assert_eq!(IBChain::transfer_restrictions(&1, &2, asset_id), RESTRICTION_FROZEN | RESTRICTION_OPTED_OUT);
+            assert_ok!(IBChain::unfreeze(Origin::signed(1), asset_id));
+
+            // 許可リスト: 発行者は常に許可されている
+            assert_ok!(IBChain::set_requires_allowlist(Origin::signed(1), asset_id, true));
+            assert_eq!(IBChain::transfer_restrictions(&1, &3, asset_id), RESTRICTION_RECIPIENT_NOT_ALLOWLISTED);
+            assert_eq!(IBChain::transfer_restrictions(&3, &4, asset_id),
+                RESTRICTION_SENDER_NOT_ALLOWLISTED | RESTRICTION_RECIPIENT_NOT_ALLOWLISTED);
+            assert_ok!(IBChain::set_allowlisted(Origin::signed(1), asset_id, 3, true));
+            assert_ok!(IBChain::set_requires_allowlist(Origin::signed(1), asset_id, false));
+
+            // 送信先制限・送信間隔・メモ必須
+            assert_ok!(IBChain::set_restrict_destinations(Origin::signed(1), asset_id, true));
+            assert_ok!(IBChain::set_allowed_destination(Origin::signed(1), asset_id, 3, true));
+            assert_ok!(IBChain::set_cooldown(Origin::signed(1), asset_id, 5));
+            assert_ok!(IBChain::sendasset(Origin::signed(1), 3, asset_id, 10));
+            assert_ok!(IBChain::set_require_memo(Origin::signed(1), asset_id, true));
+            assert_eq!(IBChain::transfer_restrictions(&1, &3, asset_id), RESTRICTION_COOLDOWN | RESTRICTION_MEMO_REQUIRED);
+            assert_eq!(IBChain::transfer_restrictions(&3, &4, asset_id),
+                RESTRICTION_DESTINATION_NOT_ALLOWED | RESTRICTION_MEMO_REQUIRED);
+            // 発行者への送信は送信先制限を受けない
+            assert_eq!(IBChain::transfer_restrictions(&3, &1, asset_id), RESTRICTION_MEMO_REQUIRED);
+        });
+    }
+
+    #[test]
+    fn wrap_and_unwrap_check_the_base_leg_before_updating() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let base = last_issued_asset(1);
+            assert_ok!(IBChain::set_max_holders(Origin::signed(1), base, 1));
+            let asset_count = IBChain::all_asset_count();
+
+            // ラップアカウントが新しい保有者になれないため、ラップ資産も発行しない
+            assert_noop!(IBChain::wrap(Origin::signed(1), base, 30, b"wcoin".to_vec()), "Max holders reached");
+            assert_eq!(IBChain::all_asset_count(), asset_count);
+        });
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let base = last_issued_asset(1);
+            assert_ok!(IBChain::sendasset(Origin::signed(1), 2, base, 20));
+            assert_ok!(IBChain::wrap(Origin::signed(2), base, 20, b"wcoin".to_vec()));
+            let wrapped = last_issued_asset(2);
+            assert_ok!(IBChain::set_max_holders(Origin::signed(1), base, 2));
+
+            // 2 が元資産の新しい保有者になれないため、ラップ資産も焼却しない
+            assert_noop!(IBChain::unwrap(Origin::signed(2), wrapped, 10), "Max holders reached");
+            assert_eq!(IBChain::my_asset_balance((2, wrapped)), 20);
+        });
+    }
+
+    #[test]
+    fn asset_keeps_its_original_encoding() {
+        // 既存の Assets を移行せずに読めるよう、後から加えた設定は別のマップに持つ
+        let asset = Asset {
+            id: H256::default(),
+            name: b"gold".to_vec(),
+            open: true,
+        };
+        assert_eq!(asset.encode(), (H256::default(), b"gold".to_vec(), true).encode());
+    }
+
+    #[cfg(feature = "asset-audit")]
+    #[test]
+    #[should_panic(expected = "asset audit failed")]
+    fn asset_audit_catches_an_imbalance() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+            assert_ok!(IBChain::sendasset(Origin::signed(1), 2, asset_id, 10));
+
+            // 発行済量だけを書き換え、所有量の合計と食い違わせる
+            <TotalIssuedAssets<Test>>::insert(asset_id, 101);
+            let _ = IBChain::sendasset(Origin::signed(1), 2, asset_id, 10);
+        });
+    }
+
+    #[test]
+    fn transfer_ownership_to_the_same_account_changes_nothing() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+
+            assert_noop!(IBChain::_transfer_ownership(asset_id, 1, 1), "You already own this asset");
+            assert_eq!(IBChain::owned_assets(&1), vec![asset_id]);
+            assert_eq!(IBChain::owned_asset_count(&1), 1);
+        });
+    }
+
+    #[test]
+    fn holder_cap_counts_the_recipient_and_the_fund_together() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+            assert_ok!(IBChain::set_community_fund(Origin::signed(1), asset_id, Some(9), 1_000));
+            assert_ok!(IBChain::set_max_holders(Origin::signed(1), asset_id, 2));
+
+            // 受信者と基金はそれぞれなら上限内だが、合わせると 3 人になる
+            assert_noop!(IBChain::sendasset(Origin::signed(1), 2, asset_id, 50), "Max holders reached");
+            assert_eq!(IBChain::my_asset_balance((2, asset_id)), 0);
+            assert_eq!(IBChain::my_asset_balance((9, asset_id)), 0);
+            assert_eq!(IBChain::holder_count(asset_id), 1);
+
+            // exit_all も何も送らずに失敗する
+            assert_noop!(IBChain::exit_all(Origin::signed(1), 2), "Max holders reached");
+
+            // 全量送信なら送信者が外れるため、上限内に収まる
+            assert_ok!(IBChain::sendasset(Origin::signed(1), 2, asset_id, 100));
+            assert_eq!(IBChain::my_asset_balance((2, asset_id)), 90);
+            assert_eq!(IBChain::my_asset_balance((9, asset_id)), 10);
+            assert_eq!(IBChain::holder_count(asset_id), 2);
+        });
+    }
+
+    #[test]
+    fn send_asset_batch_checks_the_holder_cap_before_sending() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+            assert_ok!(IBChain::set_max_holders(Origin::signed(1), asset_id, 3));
+
+            // 3 人目までは入るが、4 人目で上限を超える
+            assert_noop!(
+                IBChain::send_asset_batch(Origin::signed(1), asset_id, vec![(2, 10), (3, 10), (4, 10)], false),
+                "Max holders reached"
+            );
+            assert_eq!(IBChain::my_asset_balance((2, asset_id)), 0);
+            assert_eq!(IBChain::holder_count(asset_id), 1);
+
+            // 基金も新しい保有者として数える
+            assert_ok!(IBChain::set_community_fund(Origin::signed(1), asset_id, Some(9), 1_000));
+            assert_noop!(
+                IBChain::send_asset_batch(Origin::signed(1), asset_id, vec![(2, 10), (3, 10)], false),
+                "Max holders reached"
+            );
+            assert_eq!(IBChain::my_asset_balance((2, asset_id)), 0);
+
+            assert_ok!(IBChain::send_asset_batch(Origin::signed(1), asset_id, vec![(2, 10)], false));
+            assert_eq!(IBChain::holder_count(asset_id), 3);
+        });
+    }
+
+    #[test]
+    fn fungible_transfer_applies_the_burn() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+            assert_ok!(IBChain::set_burn_bps(Origin::signed(1), asset_id, 1_000));
+            let nonce = IBChain::account_tx_nonce(&1);
+
+            assert_ok!(<IBChain as Fungible<u64, H256>>::transfer(&1, &2, asset_id, 30));
+            assert_eq!(IBChain::my_asset_balance((1, asset_id)), 70);
+            assert_eq!(IBChain::my_asset_balance((2, asset_id)), 27);
+            assert_eq!(IBChain::total_issued_asset(asset_id), 97);
+            // sendasset と同じイベントを発行する
+            assert!(ibchain_events().iter().any(|event| match event {
+                RawEvent::SentAsset(1, 2, id, 27, _, _) => *id == asset_id,
+                _ => false,
+            }));
+            assert_eq!(IBChain::account_tx_nonce(&1), nonce + 1);
+        });
+    }
+
+    #[test]
+    fn opted_out_accounts_are_not_credited_at_issuance() {
+        with_externalities(&mut new_test_ext(), || {
+            // 資産名から決まる ID は発行前に拒否できる
+            let named = BlakeTwo256::hash_of(&b"gold".to_vec());
+            assert_ok!(IBChain::opt_out(Origin::signed(1), named));
+            assert_noop!(
+                IBChain::issue_named(Origin::signed(1), b"gold".to_vec(), 100, true),
+                "Recipient opted out of this asset"
+            );
+
+            let next = IBChain::_next_asset_id(&1);
+            assert_ok!(IBChain::opt_out(Origin::signed(3), next));
+            assert_noop!(
+                IBChain::issue_and_distribute(Origin::signed(1), b"sale".to_vec(), false, vec![(2, 10), (3, 10)]),
+                "Recipient opted out of this asset"
+            );
+            assert_eq!(IBChain::all_asset_count(), 0);
+
+            assert_ok!(IBChain::issue_and_distribute(Origin::signed(1), b"sale".to_vec(), false, vec![(2, 10)]));
+            assert_eq!(last_issued_asset(1), next);
+        });
+    }
+
+    #[test]
+    fn owner_rotation_is_timelocked_by_default() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_eq!(IBChain::owner_rotation_delay(), DEFAULT_OWNER_ROTATION_DELAY);
+            assert_noop!(IBChain::set_owner_rotation_delay(Origin::ROOT, 0), "Owner rotation delay must not be zero");
+
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 100, true));
+            let asset_id = last_issued_asset(1);
+            System::set_block_number(1);
+            assert_ok!(IBChain::begin_owner_rotation(Origin::signed(1), asset_id, 2));
+            assert_eq!(IBChain::owner_rotation(asset_id), Some((2, 1 + DEFAULT_OWNER_ROTATION_DELAY)));
+            assert_noop!(IBChain::finalize_owner_rotation(Origin::signed(2), asset_id), "Owner rotation is still timelocked");
+        });
+    }
+
+    #[test]
+    fn wrap_takes_the_base_asset_deductions_when_locking() {
+        with_externalities(&mut new_test_ext(), || {
+            assert_ok!(IBChain::issue(Origin::signed(1), b"coin".to_vec(), 200, true));
+            let base = last_issued_asset(1);
+            assert_ok!(IBChain::sendasset(Origin::signed(1), 2, base, 100));
+            assert_ok!(IBChain::set_community_fund(Origin::signed(1), base, Some(9), 1_000));
+            assert_ok!(IBChain::set_burn_bps(Origin::signed(1), base, 1_000));
+
+            // 分配 10% + 焼却 10% を差し引いた 80 をロックし、80 を発行する
+            assert_ok!(IBChain::wrap(Origin::signed(2), base, 100, b"wcoin".to_vec()));
+            let wrapped = last_issued_asset(2);
+            assert_eq!(IBChain::my_asset_balance((2, base)), 0);
+            assert_eq!(IBChain::my_asset_balance((9, base)), 10);
+            assert_eq!(IBChain::my_asset_balance((IBChain::wrap_account(), base)), 80);
+            assert_eq!(IBChain::total_issued_asset(base), 190);
+            assert_eq!(IBChain::total_issued_asset(wrapped), 80);
+            assert_eq!(IBChain::wrapped_backing(wrapped), Some((base, 80)));
+
+            // ラップ資産を送ってアンラップしても、元資産の控除は 1 回かかっている
+            assert_ok!(IBChain::sendasset(Origin::signed(2), 3, wrapped, 80));
+            assert_ok!(IBChain::unwrap(Origin::signed(3), wrapped, 80));
+            assert_eq!(IBChain::my_asset_balance((3, base)), 80);
+            assert!(IBChain::verify_asset(base));
+        });
+    }
+
+    #[test]
+    fn only_root_can_set_min_issue_qty() {
+        with_externalities(&mut new_test_ext(), || {
+            assert!(IBChain::set_min_issue_qty(Origin::signed(1), 100).is_err());
+            assert_ok!(IBChain::set_min_issue_qty(Origin::ROOT, 100));
+            assert_eq!(IBChain::min_issue_qty(), 100);
+        });
     }
 }
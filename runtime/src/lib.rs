@@ -18,7 +18,7 @@ use runtime_primitives::{
 };
 use client::{
 	block_builder::api::{CheckInherentsResult, InherentData, self as block_builder_api},
-	runtime_api, impl_runtime_apis
+	runtime_api, impl_runtime_apis, decl_runtime_apis
 };
 use version::RuntimeVersion;
 #[cfg(feature = "std")]
@@ -33,6 +33,16 @@ pub use balances::Call as BalancesCall;
 pub use runtime_primitives::{Permill, Perbill};
 pub use timestamp::BlockPeriod;
 pub use support::{StorageValue, construct_runtime};
+pub use ibchain::{NamePolicy, EVENTS_OFF, EVENTS_CORE, EVENTS_DETAILED};
+// Types appearing in `IbchainApi` signatures and `Call` arguments, so node and client code can name them.
+pub use ibchain::{Asset, AccountOverview, IssuancePolicy, AssetStats, AssetConfig, AdminAction};
+pub use ibchain::{
+	RESTRICTION_UNKNOWN_ASSET, RESTRICTION_FROZEN, RESTRICTION_RESERVED_RECIPIENT, RESTRICTION_OPTED_OUT,
+	RESTRICTION_SENDER_NOT_ALLOWLISTED, RESTRICTION_RECIPIENT_NOT_ALLOWLISTED,
+	RESTRICTION_DESTINATION_NOT_ALLOWED, RESTRICTION_COOLDOWN, RESTRICTION_MEMO_REQUIRED,
+};
+#[cfg(feature = "std")]
+pub use ibchain::{AssetInfo, CompactAssetInfo, encode_compact_id, decode_compact_id};
 
 /// Alias to Ed25519 pubkey that identifies an account on the chain.
 pub type AccountId = primitives::H256;
@@ -50,6 +60,11 @@ pub type Nonce = u64;
 mod substratekitties;
 // Add this line
 mod ibchain;
+/// Conversions between raw asset quantities and whole/fractional display values.
+pub mod format;
+/// Benchmark setups for weighting ibchain calls.
+#[cfg(feature = "runtime-benchmarks")]
+pub mod benchmarking;
 
 /// Used for the module template in `./template.rs`
 mod template;
@@ -187,6 +202,7 @@ impl substratekitties::Trait for Runtime {
 
 impl ibchain::Trait for Runtime {
 	type Event = Event;
+	type Signature = Ed25519Signature;
 } // IBChain
 
 /// Used for the module template in `./template.rs`
@@ -211,7 +227,7 @@ construct_runtime!(
 		// Add this line
     Substratekitties: substratekitties::{Module, Call, Storage, Event<T>},
 		// IBChain
-		IBChain: ibchain::{Module, Call, Storage, Event<T>},
+		IBChain: ibchain::{Module, Call, Storage, Config<T>, Event<T>},
 		// Used for the module template in `./template.rs`
 		TemplateModule: template::{Module, Call, Storage, Event<T>},
 	}
@@ -234,6 +250,98 @@ pub type CheckedExtrinsic = generic::CheckedExtrinsic<AccountId, Nonce, Call>;
 /// Executive: handles dispatch to the various modules.
 pub type Executive = executive::Executive<Runtime, Block, Context, Fees, AllModules>;
 
+/// Non-zero asset holdings of the sudo key as (id, name, balance), for inspecting
+/// the root account on the IB testnet.
+pub fn root_portfolio() -> Vec<(Hash, Vec<u8>, u64)> {
+	IBChain::portfolio(&Sudo::key())
+}
+
+// IBChain
+decl_runtime_apis! {
+	/// Read-only queries over the IBChain asset registry, reachable through `state_call`.
+	pub trait IbchainApi {
+		/// Assets the given account may currently mint more of.
+		fn mintable_assets(account: AccountId) -> Vec<Hash>;
+		/// Recent (block number, total supply) checkpoints of an asset, oldest first.
+		fn supply_history(asset_id: Hash) -> Vec<(BlockNumber, u64)>;
+		/// Held and issued assets of an account in one response, each capped at 1000
+		/// entries with a flag saying whether more exist.
+		fn account_overview(account: AccountId) -> ibchain::AccountOverview<Hash>;
+		/// Held assets of an account from index `start`, at most `limit` (up to 1000).
+		fn my_assets_page(account: AccountId, start: u64, limit: u64) -> Vec<(Hash, u64)>;
+		/// Non-zero holdings of the sudo key as (id, name, balance), among its first 1000 assets.
+		fn root_portfolio() -> Vec<(Hash, Vec<u8>, u64)>;
+		/// Issued assets of an account from index `start`, at most `limit` (up to 1000).
+		fn owned_assets_page(account: AccountId, start: u64, limit: u64) -> Vec<Hash>;
+		/// Assets issued together under a collection name, in issue order.
+		fn collection_assets(name: Vec<u8>) -> Vec<Hash>;
+		/// Constraints `issue` currently enforces, for validating input before signing.
+		fn issuance_policy() -> ibchain::IssuancePolicy;
+		/// Supply, holder count and cumulative transfer volume of an asset.
+		fn asset_stats(asset_id: Hash) -> ibchain::AssetStats;
+		/// Every transfer and issuance setting of an asset in one struct, or `None` if the
+		/// asset does not exist.
+		fn asset_config(asset_id: Hash) -> Option<ibchain::AssetConfig<AccountId, BlockNumber>>;
+		/// Rough number of storage bytes an asset uses, computed from its holder count
+		/// rather than by reading every entry. 0 for unknown assets.
+		fn storage_footprint(asset_id: Hash) -> u64;
+		/// Up to `n` (at most 100) holders of an asset, largest balance first.
+		fn top_holders(asset_id: Hash, n: u32) -> Vec<(AccountId, u64)>;
+		/// Non-zero balances of an asset's first 1000 registered holders, in registration
+		/// order. The result can be passed to `import_snapshot`.
+		fn snapshot(asset_id: Hash) -> Vec<(AccountId, u64)>;
+		/// Recent privileged owner actions on an asset as (block number, action name), oldest first.
+		fn owner_action_log(asset_id: Hash) -> Vec<(BlockNumber, Vec<u8>)>;
+		/// ERC-20 style `balanceOf`.
+		fn balance_of(asset_id: Hash, account: AccountId) -> u64;
+		/// ERC-20 style `totalSupply`.
+		fn total_supply(asset_id: Hash) -> u64;
+		/// Total issued amount, or `None` if the asset does not exist. Unlike `total_supply`,
+		/// this tells a fully burned asset (`Some(0)`) apart from an unknown id.
+		fn try_total_issued(asset_id: Hash) -> Option<u64>;
+		/// ERC-20 style `allowance`.
+		fn allowance(asset_id: Hash, owner: AccountId, spender: AccountId) -> u64;
+		/// Restrictions currently blocking a transfer from `from` to `to`, as a bitmask of
+		/// the `RESTRICTION_*` constants. 0 means none; balances and vote locks are not checked.
+		fn transfer_restrictions(from: AccountId, to: AccountId, asset_id: Hash) -> u32;
+		/// Asset at `index` in the global asset list, or `None` past `all_asset_count`.
+		fn asset_by_index(index: u64) -> Option<Hash>;
+		/// (id, name) of the assets whose `open` flag matches, among global asset list
+		/// entries `start..start + limit` (`limit` up to 1000). Pages may come back short;
+		/// continue from `start + limit`.
+		fn assets_by_open(open: bool, start: u64, limit: u64) -> Vec<(Hash, Vec<u8>)>;
+		/// Whether holder balances of an asset add up to its supply. Reads every
+		/// registered holder; assets with more than 1000 holders report `false`.
+		fn verify_asset(asset_id: Hash) -> bool;
+		/// How much more of an asset the account may mint, or `None` if it may not mint it.
+		fn mint_capacity(account: AccountId, asset_id: Hash) -> Option<u64>;
+		/// How much more of an asset can ever be minted: `u64::MAX` minus the issued total
+		/// for open assets, 0 for closed or unknown ones.
+		fn remaining_mintable(asset_id: Hash) -> u64;
+		/// Payment recorded under an invoice id as (payer, payee, asset, amount, block number).
+		fn invoice(invoice_id: Hash) -> Option<(AccountId, AccountId, Hash, u64, BlockNumber)>;
+		/// Allowances granted to a spender as (owner, asset, amount), at most 100 entries.
+		fn approvals_for_spender(spender: AccountId) -> Vec<(AccountId, Hash, u64)>;
+		/// (total, spendable) balance of an account, with locks active at the current block deducted.
+		fn spendable_balance(account: AccountId, asset_id: Hash) -> (u64, u64);
+		/// Balance for governance snapshots. Call it at the block hash the proposal was
+		/// created in; the result is the balance in that block's state, provided the node
+		/// still keeps it (archive nodes, or within the pruning window).
+		fn balance_at(account: AccountId, asset_id: Hash) -> u64;
+		/// Total supply for governance snapshots, read like `balance_at`.
+		fn total_supply_at(asset_id: Hash) -> u64;
+		/// Stored asset record, or `None` if it does not exist. RPC layers convert it
+		/// with `AssetInfo::from` to present the name as a string, and further with
+		/// `CompactAssetInfo::from` when the id should be short base64url instead of hex.
+		fn asset(asset_id: Hash) -> Option<ibchain::Asset<Hash>>;
+		/// Ids of pending transfers the account sends or receives, at most 50.
+		fn escrows_of(account: AccountId) -> Vec<Hash>;
+		/// Balances of every asset either account holds, as (asset, a's balance, b's balance),
+		/// at most 1000 entries.
+		fn holdings_diff(a: AccountId, b: AccountId) -> Vec<(Hash, u64, u64)>;
+	}
+}
+
 // Implement our runtime API endpoints. This is just a bunch of proxying.
 impl_runtime_apis! {
 	impl runtime_api::Core<Block> for Runtime {
@@ -293,4 +401,134 @@ impl_runtime_apis! {
 			Aura::slot_duration()
 		}
 	}
+
+	impl self::IbchainApi<Block> for Runtime {
+		fn mintable_assets(account: AccountId) -> Vec<Hash> {
+			IBChain::mintable_assets(&account)
+		}
+
+		fn supply_history(asset_id: Hash) -> Vec<(BlockNumber, u64)> {
+			IBChain::supply_history(asset_id)
+		}
+
+		fn account_overview(account: AccountId) -> ibchain::AccountOverview<Hash> {
+			IBChain::account_overview(&account)
+		}
+
+		fn my_assets_page(account: AccountId, start: u64, limit: u64) -> Vec<(Hash, u64)> {
+			IBChain::my_assets_page(&account, start, limit)
+		}
+
+		fn root_portfolio() -> Vec<(Hash, Vec<u8>, u64)> {
+			root_portfolio()
+		}
+
+		fn owned_assets_page(account: AccountId, start: u64, limit: u64) -> Vec<Hash> {
+			IBChain::owned_assets_page(&account, start, limit)
+		}
+
+		fn collection_assets(name: Vec<u8>) -> Vec<Hash> {
+			IBChain::collection_assets(name)
+		}
+
+		fn issuance_policy() -> ibchain::IssuancePolicy {
+			IBChain::issuance_policy()
+		}
+
+		fn asset_stats(asset_id: Hash) -> ibchain::AssetStats {
+			IBChain::asset_stats(asset_id)
+		}
+
+		fn asset_config(asset_id: Hash) -> Option<ibchain::AssetConfig<AccountId, BlockNumber>> {
+			IBChain::asset_config(asset_id)
+		}
+
+		fn storage_footprint(asset_id: Hash) -> u64 {
+			IBChain::storage_footprint(asset_id)
+		}
+
+		fn top_holders(asset_id: Hash, n: u32) -> Vec<(AccountId, u64)> {
+			IBChain::top_holders(asset_id, n)
+		}
+
+		fn snapshot(asset_id: Hash) -> Vec<(AccountId, u64)> {
+			IBChain::snapshot(asset_id)
+		}
+
+		fn owner_action_log(asset_id: Hash) -> Vec<(BlockNumber, Vec<u8>)> {
+			IBChain::owner_action_log(asset_id)
+		}
+
+		fn balance_of(asset_id: Hash, account: AccountId) -> u64 {
+			IBChain::balance_of(asset_id, &account)
+		}
+
+		fn total_supply(asset_id: Hash) -> u64 {
+			IBChain::total_supply(asset_id)
+		}
+
+		fn try_total_issued(asset_id: Hash) -> Option<u64> {
+			IBChain::try_total_issued(asset_id)
+		}
+
+		fn allowance(asset_id: Hash, owner: AccountId, spender: AccountId) -> u64 {
+			IBChain::allowance((owner, spender, asset_id))
+		}
+
+		fn transfer_restrictions(from: AccountId, to: AccountId, asset_id: Hash) -> u32 {
+			IBChain::transfer_restrictions(&from, &to, asset_id)
+		}
+
+		fn asset_by_index(index: u64) -> Option<Hash> {
+			IBChain::try_asset_by_index(index)
+		}
+
+		fn assets_by_open(open: bool, start: u64, limit: u64) -> Vec<(Hash, Vec<u8>)> {
+			IBChain::assets_by_open(open, start, limit)
+		}
+
+		fn verify_asset(asset_id: Hash) -> bool {
+			IBChain::verify_asset(asset_id)
+		}
+
+		fn mint_capacity(account: AccountId, asset_id: Hash) -> Option<u64> {
+			IBChain::mint_capacity(&account, asset_id)
+		}
+
+		fn remaining_mintable(asset_id: Hash) -> u64 {
+			IBChain::remaining_mintable(asset_id)
+		}
+
+		fn invoice(invoice_id: Hash) -> Option<(AccountId, AccountId, Hash, u64, BlockNumber)> {
+			IBChain::invoice(invoice_id)
+		}
+
+		fn approvals_for_spender(spender: AccountId) -> Vec<(AccountId, Hash, u64)> {
+			IBChain::approvals_for_spender(&spender)
+		}
+
+		fn spendable_balance(account: AccountId, asset_id: Hash) -> (u64, u64) {
+			IBChain::total_and_spendable(&account, asset_id)
+		}
+
+		fn balance_at(account: AccountId, asset_id: Hash) -> u64 {
+			IBChain::balance_at(&account, asset_id)
+		}
+
+		fn total_supply_at(asset_id: Hash) -> u64 {
+			IBChain::total_supply_at(asset_id)
+		}
+
+		fn asset(asset_id: Hash) -> Option<ibchain::Asset<Hash>> {
+			IBChain::try_asset(asset_id)
+		}
+
+		fn escrows_of(account: AccountId) -> Vec<Hash> {
+			IBChain::escrows_of(account)
+		}
+
+		fn holdings_diff(a: AccountId, b: AccountId) -> Vec<(Hash, u64, u64)> {
+			IBChain::holdings_diff(&a, &b)
+		}
+	}
 }
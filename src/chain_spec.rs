@@ -1,9 +1,10 @@
 use primitives::{Ed25519AuthorityId, ed25519};
 use node_template_runtime::{
 	AccountId, GenesisConfig, ConsensusConfig, TimestampConfig, BalancesConfig,
-	SudoConfig, IndicesConfig, FeesConfig,
+	SudoConfig, IndicesConfig, FeesConfig, IBChainConfig, NamePolicy, EVENTS_DETAILED,
 };
-use substrate_service;
+use substrate_service::{self, Properties};
+use serde_json::json;
 
 // Note this is the URL for the telemetry server
 //const STAGING_TELEMETRY_URL: &str = "wss://telemetry.polkadot.io/submit/";
@@ -26,27 +27,33 @@ pub enum Alternative {
 impl Alternative {
 	/// Get an actual chain config from one of the alternatives.
 	pub(crate) fn load(self) -> Result<ChainSpec, String> {
+		let block_period = self.block_period();
+		let genesis_assets = self.genesis_assets();
+		let properties = self.properties();
 		Ok(match self {
 			Alternative::Development => ChainSpec::from_genesis(
 				"Development",
 				"dev",
-				|| testnet_genesis(vec![
+				move || testnet_genesis(vec![
 					ed25519::Pair::from_seed(b"Alice                           ").public().into(),
 				], vec![
 					ed25519::Pair::from_seed(b"Alice                           ").public().0.into(),
 				],
-					ed25519::Pair::from_seed(b"Alice                           ").public().0.into()
+					ed25519::Pair::from_seed(b"Alice                           ").public().0.into(),
+					1,
+					block_period,
+					genesis_assets.clone(),
 				),
 				vec![],
 				None,
 				None,
 				None,
-				None
+				Some(properties.clone())
 			),
 			Alternative::LocalTestnet => ChainSpec::from_genesis(
 				"Local Testnet",
 				"local_testnet",
-				|| testnet_genesis(vec![
+				move || testnet_genesis(vec![
 					ed25519::Pair::from_seed(b"Alice                           ").public().into(),
 					ed25519::Pair::from_seed(b"Bob                             ").public().into(),
 				], vec![
@@ -57,32 +64,68 @@ impl Alternative {
 					ed25519::Pair::from_seed(b"Eve                             ").public().0.into(),
 					ed25519::Pair::from_seed(b"Ferdie                          ").public().0.into(),
 				],
-					ed25519::Pair::from_seed(b"Alice                           ").public().0.into()
+					ed25519::Pair::from_seed(b"Alice                           ").public().0.into(),
+					1,
+					block_period,
+					genesis_assets.clone(),
 				),
 				vec![],
 				None,
 				None,
 				None,
-				None
+				Some(properties.clone())
 			),
 			Alternative::IbTestnet => ChainSpec::from_genesis(
 				"IB Chain",
 				"ib_chain",
-				|| testnet_genesis(vec![
+				move || testnet_genesis(vec![
 					ed25519::Pair::from_seed(b"Matsuda                         ").public().into(),
 				], vec![
 					ed25519::Pair::from_seed(b"Matsuda                         ").public().0.into(),
 				],
 					ed25519::Pair::from_seed(b"Matsuda                         ").public().0.into(),
+					100,
+					block_period,
+					genesis_assets.clone(),
 				),
 				vec![],
 				None,
 				None,
 				None,
-				None
+				Some(properties.clone())
 			),
 		})
 	}
+
+	/// Block time in seconds for this chain.
+	pub(crate) fn block_period(&self) -> u64 {
+		match self {
+			Alternative::Development | Alternative::LocalTestnet => 5,
+			Alternative::IbTestnet => 6,
+		}
+	}
+
+	/// Token display properties wallets read for this chain.
+	pub(crate) fn properties(&self) -> Properties {
+		match self {
+			Alternative::Development => chain_properties("DEV", 12),
+			Alternative::LocalTestnet => chain_properties("UNIT", 12),
+			Alternative::IbTestnet => chain_properties("IB", 10),
+		}
+	}
+
+	/// Assets issued at genesis as (owner, name, supply).
+	pub(crate) fn genesis_assets(&self) -> Vec<(AccountId, Vec<u8>, u64)> {
+		match self {
+			Alternative::Development | Alternative::LocalTestnet => vec![],
+			Alternative::IbTestnet => vec![(
+				ed25519::Pair::from_seed(b"Matsuda                         ").public().0.into(),
+				b"IB".to_vec(),
+				IB_GENESIS_SUPPLY,
+			)],
+		}
+	}
+
 	// $ subkey restore Matsuda
 	//Seed 0x4d61747375646120202020202020202020202020202020202020202020202020 is account:
   // Public key (hex): 0x9d361c06d36e411422e49d14d00f5128662b8f84567400ee26aaeb3cfc8f7f77
@@ -98,7 +141,22 @@ impl Alternative {
 	}
 }
 
-fn testnet_genesis(initial_authorities: Vec<Ed25519AuthorityId>, endowed_accounts: Vec<AccountId>, root_key: AccountId) -> GenesisConfig {
+/// `ChainSpec` properties carrying a token symbol and its number of decimals.
+fn chain_properties(symbol: &str, decimals: u8) -> Properties {
+	match json!({
+		"tokenSymbol": symbol,
+		"tokenDecimals": decimals,
+	}) {
+		serde_json::Value::Object(properties) => properties,
+		_ => unreachable!("a JSON object literal is an object"),
+	}
+}
+
+/// Fixed supply of the canonical IB asset, in raw units (100,000,000 IB at 10 decimals).
+/// It is issued at genesis to the sudo key of the IB chain.
+const IB_GENESIS_SUPPLY: u64 = 100_000_000 * 10_000_000_000;
+
+fn testnet_genesis(initial_authorities: Vec<Ed25519AuthorityId>, endowed_accounts: Vec<AccountId>, root_key: AccountId, min_issue_qty: u64, block_period: u64, genesis_assets: Vec<(AccountId, Vec<u8>, u64)>) -> GenesisConfig {
 	GenesisConfig {
 		consensus: Some(ConsensusConfig {
 			code: include_bytes!("../runtime/wasm/target/wasm32-unknown-unknown/release/node_template_runtime_wasm.compact.wasm").to_vec(),
@@ -106,7 +164,7 @@ fn testnet_genesis(initial_authorities: Vec<Ed25519AuthorityId>, endowed_account
 		}),
 		system: None,
 		timestamp: Some(TimestampConfig {
-			period: 5,					// 5 second block time.
+			period: block_period,
 		}),
 		indices: Some(IndicesConfig {
 			ids: endowed_accounts.clone(),
@@ -124,6 +182,94 @@ fn testnet_genesis(initial_authorities: Vec<Ed25519AuthorityId>, endowed_account
 		fees: Some(FeesConfig {
 			transaction_base_fee: 0,
 			transaction_byte_fee: 0,
-		})
+		}),
+		ibchain: Some(IBChainConfig {
+			min_issue_qty,
+			name_policy: NamePolicy::Printable,
+			event_verbosity: EVENTS_DETAILED,
+			genesis_assets,
+		}),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use primitives::Blake2Hasher;
+	use node_template_runtime::BuildStorage;
+
+	#[test]
+	fn block_period_per_alternative() {
+		assert_eq!(Alternative::Development.block_period(), 5);
+		assert_eq!(Alternative::LocalTestnet.block_period(), 5);
+		assert_eq!(Alternative::IbTestnet.block_period(), 6);
+	}
+
+	#[test]
+	fn ib_testnet_carries_token_properties() {
+		let spec = Alternative::IbTestnet.load().unwrap();
+		assert_eq!(serde_json::Value::Object(spec.properties()), json!({
+			"tokenSymbol": "IB",
+			"tokenDecimals": 10,
+		}));
+	}
+
+	#[test]
+	fn each_alternative_has_its_own_token_properties() {
+		let expected = vec![
+			(Alternative::Development, json!({ "tokenSymbol": "DEV", "tokenDecimals": 12 })),
+			(Alternative::LocalTestnet, json!({ "tokenSymbol": "UNIT", "tokenDecimals": 12 })),
+			(Alternative::IbTestnet, json!({ "tokenSymbol": "IB", "tokenDecimals": 10 })),
+		];
+		for (alternative, properties) in expected {
+			assert_eq!(serde_json::Value::Object(alternative.properties()), properties);
+			assert_eq!(serde_json::Value::Object(alternative.load().unwrap().properties()), properties);
+		}
+		assert_ne!(Alternative::Development.properties(), Alternative::LocalTestnet.properties());
+	}
+
+	#[test]
+	fn only_ib_testnet_has_genesis_asset() {
+		let root: AccountId = ed25519::Pair::from_seed(b"Matsuda                         ").public().0.into();
+		let assets = Alternative::IbTestnet.genesis_assets();
+		assert_eq!(assets, vec![(root, b"IB".to_vec(), IB_GENESIS_SUPPLY)]);
+		let genesis = testnet_genesis(vec![], vec![], root, 100, 6, assets.clone());
+		assert_eq!(genesis.ibchain.unwrap().genesis_assets, assets);
+
+		assert!(Alternative::Development.genesis_assets().is_empty());
+		assert!(Alternative::LocalTestnet.genesis_assets().is_empty());
+	}
+
+	#[test]
+	fn ib_root_key_is_endowed_and_owns_genesis_asset() {
+		let root: AccountId = ed25519::Pair::from_seed(b"Matsuda                         ").public().0.into();
+		let genesis = testnet_genesis(vec![], vec![root], root, 100, 6, Alternative::IbTestnet.genesis_assets());
+
+		assert_eq!(genesis.sudo.unwrap().key, root);
+		assert_eq!(genesis.balances.unwrap().balances, vec![(root, 1 << 60)]);
+		// The root key is listed once as an asset owner, independently of its native endowment.
+		assert_eq!(genesis.ibchain.unwrap().genesis_assets, vec![(root, b"IB".to_vec(), IB_GENESIS_SUPPLY)]);
+	}
+
+	#[test]
+	fn root_portfolio_lists_the_genesis_asset() {
+		let root: AccountId = ed25519::Pair::from_seed(b"Matsuda                         ").public().0.into();
+		let genesis = testnet_genesis(vec![], vec![root], root, 100, 6, Alternative::IbTestnet.genesis_assets());
+		let mut ext: sr_io::TestExternalities<Blake2Hasher> = genesis.build_storage().unwrap().0.into();
+
+		sr_io::with_externalities(&mut ext, || {
+			let portfolio = node_template_runtime::root_portfolio();
+			assert_eq!(portfolio.len(), 1);
+			assert_eq!((portfolio[0].1.clone(), portfolio[0].2), (b"IB".to_vec(), IB_GENESIS_SUPPLY));
+		});
+	}
+
+	#[test]
+	fn testnet_genesis_uses_block_period() {
+		for alternative in vec![Alternative::Development, Alternative::LocalTestnet, Alternative::IbTestnet] {
+			let period = alternative.block_period();
+			let genesis = testnet_genesis(vec![], vec![], Default::default(), 1, period, vec![]);
+			assert_eq!(genesis.timestamp.unwrap().period, period);
+		}
 	}
 }